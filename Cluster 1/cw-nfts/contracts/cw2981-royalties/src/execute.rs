@@ -0,0 +1,40 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response};
+
+use crate::{ContractError, Cw2981Contract};
+use crate::state::{DefaultRoyalty, DEFAULT_ROYALTY};
+
+/// Basis points of the whole sale price
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+pub fn set_default_royalty(
+    deps: DepsMut,
+    info: MessageInfo,
+    payment_address: String,
+    basis_points: u16,
+) -> Result<Response, ContractError> {
+    Cw2981Contract::default().check_minter(deps.storage, &info.sender)?;
+    if basis_points > MAX_BASIS_POINTS {
+        return Err(ContractError::RoyaltyTooHigh {
+            basis_points,
+            max: MAX_BASIS_POINTS,
+        });
+    }
+
+    let royalty = DefaultRoyalty {
+        payment_address: deps.api.addr_validate(&payment_address)?,
+        basis_points,
+    };
+    DEFAULT_ROYALTY.save(deps.storage, &royalty)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_default_royalty")
+        .add_attribute("payment_address", payment_address)
+        .add_attribute("basis_points", basis_points.to_string()))
+}
+
+pub fn remove_default_royalty(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    Cw2981Contract::default().check_minter(deps.storage, &info.sender)?;
+    DEFAULT_ROYALTY.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "remove_default_royalty"))
+}
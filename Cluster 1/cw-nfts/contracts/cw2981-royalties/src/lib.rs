@@ -1,14 +1,19 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Empty, to_binary};
 use cw2::set_contract_version;
-pub use cw721_base::{ContractError, InstantiateMsg, MinterResponse, MintMsg};
+pub use cw721_base::{InstantiateMsg, MigrateMsg, MinterResponse, MintMsg};
 use cw721_base::Cw721Contract;
-pub use query::{check_royalties, query_royalties_info};
+pub use error::ContractError;
+pub use execute::{remove_default_royalty, set_default_royalty};
+pub use query::{check_royalties, query_default_royalty, query_royalties_info};
 
-use crate::msg::Cw2981QueryMsg;
+use crate::msg::{Cw2981ExecuteMsg, Cw2981QueryMsg};
 
+mod error;
+pub mod execute;
 pub mod msg;
 pub mod query;
+pub mod state;
 
 // Version info for migration
 const CONTRACT_NAME: &str = "crates.io:cw2981-royalties";
@@ -47,17 +52,33 @@ pub type Extension = Option<Metadata>;
 
 pub type MintExtension = Option<Extension>;
 
-pub type Cw2981Contract<'a> = Cw721Contract<'a, Extension, Empty, Empty, Cw2981QueryMsg>;
-pub type ExecuteMsg = cw721_base::ExecuteMsg<Extension, Empty>;
+pub type Cw2981Contract<'a> =
+    Cw721Contract<'a, Extension, Empty, Cw2981ExecuteMsg, Cw2981QueryMsg>;
+pub type ExecuteMsg = cw721_base::ExecuteMsg<Extension, Cw2981ExecuteMsg>;
 pub type QueryMsg = cw721_base::QueryMsg<Cw2981QueryMsg>;
 
 #[cfg(not(feature = "library"))]
 pub mod entry {
-    use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+    use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
     use cosmwasm_std::entry_point;
 
+    use crate::state::TOKEN_ROYALTIES;
+
     use super::*;
 
+    /// Notes that a token carries its own royalty, so CheckRoyalties reports royalties are owed.
+    /// Every mint and metadata update can set a royalty_percentage.
+    fn track_token_royalty(storage: &mut dyn Storage, extension: &Extension) -> StdResult<()> {
+        if let Some(Metadata {
+            royalty_percentage: Some(_),
+            ..
+        }) = extension
+        {
+            TOKEN_ROYALTIES.save(storage, &true)?;
+        }
+        Ok(())
+    }
+
     #[entry_point]
     pub fn instantiate(
         mut deps: DepsMut,
@@ -69,6 +90,7 @@ pub mod entry {
         // Explicitly set contract name and version, otherwise set to cw721-base info
         set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)
             .map_err(ContractError::Std)?;
+        TOKEN_ROYALTIES.save(deps.storage, &false)?;
         Ok(res)
     }
 
@@ -79,7 +101,40 @@ pub mod entry {
         info: MessageInfo,
         msg: ExecuteMsg,
     ) -> Result<Response, ContractError> {
-        Cw2981Contract::default().execute(deps, env, info, msg)
+        match msg {
+            ExecuteMsg::Extension { msg } => match msg {
+                Cw2981ExecuteMsg::SetDefaultRoyalty {
+                    payment_address,
+                    basis_points,
+                } => set_default_royalty(deps, info, payment_address, basis_points),
+                Cw2981ExecuteMsg::RemoveDefaultRoyalty {} => remove_default_royalty(deps, info),
+            },
+            ExecuteMsg::Mint(msg) => {
+                track_token_royalty(deps.storage, &msg.extension)?;
+                Ok(Cw2981Contract::default().mint(deps, env, info, msg)?)
+            }
+            ExecuteMsg::BatchMint { tokens } => {
+                for token in &tokens {
+                    track_token_royalty(deps.storage, &token.extension)?;
+                }
+                Ok(Cw2981Contract::default().batch_mint(deps, env, info, tokens)?)
+            }
+            ExecuteMsg::UpdateNftInfo {
+                token_id,
+                token_uri,
+                extension,
+            } => {
+                track_token_royalty(deps.storage, &extension)?;
+                Ok(Cw2981Contract::default()
+                    .update_nft_info(deps, env, info, token_id, token_uri, extension)?)
+            }
+            _ => Ok(Cw2981Contract::default().execute(deps, env, info, msg)?),
+        }
+    }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+        Ok(Cw2981Contract::default().migrate(deps, env, CONTRACT_NAME, CONTRACT_VERSION)?)
     }
 
     #[entry_point]
@@ -91,6 +146,7 @@ pub mod entry {
                     sale_price,
                 } => to_binary(&query_royalties_info(deps, token_id, sale_price)?),
                 Cw2981QueryMsg::CheckRoyalties {} => to_binary(&check_royalties(deps)?),
+                Cw2981QueryMsg::DefaultRoyalty {} => to_binary(&query_default_royalty(deps)?),
             },
             _ => Cw2981Contract::default().query(deps, env, msg),
         }
@@ -99,11 +155,12 @@ pub mod entry {
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::{from_binary, Uint128};
+    use cosmwasm_std::{Addr, DepsMut, from_binary, Uint128};
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cw721::Cw721Query;
 
-    use crate::msg::{CheckRoyaltiesResponse, RoyaltiesInfoResponse};
+    use crate::msg::{CheckRoyaltiesResponse, DefaultRoyaltyResponse, RoyaltiesInfoResponse};
+    use crate::state::DefaultRoyalty;
 
     use super::*;
 
@@ -119,12 +176,14 @@ mod tests {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
             minter: CREATOR.to_string(),
+            max_supply: None,
+            transfer_policy: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
         let token_id = "Enterprise";
         let mint_msg = MintMsg {
-            token_id: token_id.to_string(),
+            token_id: Some(token_id.to_string()),
             owner: "john".to_string(),
             token_uri: Some("https://starships.example.com/Starship/Enterprise.json".into()),
             extension: Some(Metadata {
@@ -151,12 +210,14 @@ mod tests {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
             minter: CREATOR.to_string(),
+            max_supply: None,
+            transfer_policy: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
         let token_id = "Enterprise";
         let mint_msg = MintMsg {
-            token_id: token_id.to_string(),
+            token_id: Some(token_id.to_string()),
             owner: "john".to_string(),
             token_uri: Some("https://starships.example.com/Starship/Enterprise.json".into()),
             extension: Some(Metadata {
@@ -166,6 +227,23 @@ mod tests {
             }),
         };
         let exec_msg = ExecuteMsg::Mint(mint_msg);
+        entry::execute(deps.as_mut(), mock_env(), info.clone(), exec_msg).unwrap();
+
+        // no royalties are owed until a token or the collection sets one
+        let res = check_royalties(deps.as_ref()).unwrap();
+        assert_eq!(
+            res,
+            CheckRoyaltiesResponse {
+                royalty_payments: false,
+            }
+        );
+
+        let exec_msg = ExecuteMsg::Extension {
+            msg: Cw2981ExecuteMsg::SetDefaultRoyalty {
+                payment_address: CREATOR.to_string(),
+                basis_points: 250,
+            },
+        };
         entry::execute(deps.as_mut(), mock_env(), info, exec_msg).unwrap();
 
         let expected = CheckRoyaltiesResponse {
@@ -192,12 +270,14 @@ mod tests {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
             minter: CREATOR.to_string(),
+            max_supply: None,
+            transfer_policy: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
         let token_id = "Enterprise";
         let mint_msg = MintMsg {
-            token_id: token_id.to_string(),
+            token_id: Some(token_id.to_string()),
             owner: "jeanluc".to_string(),
             token_uri: Some("https://starships.example.com/Starship/Enterprise.json".into()),
             extension: Some(Metadata {
@@ -234,7 +314,7 @@ mod tests {
         // which is the default behaviour
         let voyager_token_id = "Voyager";
         let second_mint_msg = MintMsg {
-            token_id: voyager_token_id.to_string(),
+            token_id: Some(voyager_token_id.to_string()),
             owner: "janeway".to_string(),
             token_uri: Some("https://starships.example.com/Starship/Voyager.json".into()),
             extension: Some(Metadata {
@@ -263,4 +343,196 @@ mod tests {
             .unwrap();
         assert_eq!(res, voyager_expected);
     }
+
+    fn setup_contract(deps: DepsMut) {
+        let init_msg = InstantiateMsg {
+            name: "SpaceShips".to_string(),
+            symbol: "SPACE".to_string(),
+            minter: CREATOR.to_string(),
+            max_supply: None,
+            transfer_policy: None,
+        };
+        entry::instantiate(deps, mock_env(), mock_info(CREATOR, &[]), init_msg).unwrap();
+    }
+
+    fn royalty_metadata() -> Extension {
+        Some(Metadata {
+            royalty_payment_address: Some("jeanluc".to_string()),
+            royalty_percentage: Some(10),
+            ..Metadata::default()
+        })
+    }
+
+    #[test]
+    fn check_royalties_after_batch_mint() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        let tokens = [("Defiant", None), ("Enterprise", royalty_metadata())]
+            .into_iter()
+            .map(|(token_id, extension)| MintMsg {
+                token_id: Some(token_id.to_string()),
+                owner: "jeanluc".to_string(),
+                token_uri: None,
+                extension,
+            })
+            .collect();
+        let exec_msg = ExecuteMsg::BatchMint { tokens };
+        entry::execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), exec_msg).unwrap();
+
+        let res = query_royalties_info(deps.as_ref(), "Enterprise".to_string(), Uint128::new(100))
+            .unwrap();
+        assert_eq!(res.royalty_amount, Uint128::new(10));
+        let res = check_royalties(deps.as_ref()).unwrap();
+        assert!(res.royalty_payments);
+    }
+
+    #[test]
+    fn check_royalties_after_update_nft_info() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        let mint_msg = MintMsg {
+            token_id: Some("Enterprise".to_string()),
+            owner: "jeanluc".to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        let info = mock_info(CREATOR, &[]);
+        entry::execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Mint(mint_msg))
+            .unwrap();
+        let res = check_royalties(deps.as_ref()).unwrap();
+        assert!(!res.royalty_payments);
+
+        let exec_msg = ExecuteMsg::UpdateNftInfo {
+            token_id: "Enterprise".to_string(),
+            token_uri: None,
+            extension: royalty_metadata(),
+        };
+        entry::execute(deps.as_mut(), mock_env(), info, exec_msg).unwrap();
+
+        let res = query_royalties_info(deps.as_ref(), "Enterprise".to_string(), Uint128::new(100))
+            .unwrap();
+        assert_eq!(res.royalty_amount, Uint128::new(10));
+        let res = check_royalties(deps.as_ref()).unwrap();
+        assert!(res.royalty_payments);
+    }
+
+    #[test]
+    fn check_default_royalties() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            name: "SpaceShips".to_string(),
+            symbol: "SPACE".to_string(),
+            minter: CREATOR.to_string(),
+            max_supply: None,
+            transfer_policy: None,
+        };
+        entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        // one token with its own royalty, one without
+        let mint_msg = MintMsg {
+            token_id: Some("Enterprise".to_string()),
+            owner: "jeanluc".to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                royalty_payment_address: Some("jeanluc".to_string()),
+                royalty_percentage: Some(10),
+                ..Metadata::default()
+            }),
+        };
+        entry::execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Mint(mint_msg))
+            .unwrap();
+        let mint_msg = MintMsg {
+            token_id: Some("Voyager".to_string()),
+            owner: "janeway".to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        entry::execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Mint(mint_msg))
+            .unwrap();
+
+        // without a default the plain token owes nothing
+        let res =
+            query_royalties_info(deps.as_ref(), "Voyager".to_string(), Uint128::new(1000)).unwrap();
+        assert_eq!(
+            res,
+            RoyaltiesInfoResponse {
+                address: String::from(""),
+                royalty_amount: Uint128::zero(),
+            }
+        );
+
+        // only the minter can set the default, and it cannot exceed the sale price
+        let set_msg = |basis_points| ExecuteMsg::Extension {
+            msg: Cw2981ExecuteMsg::SetDefaultRoyalty {
+                payment_address: "starfleet".to_string(),
+                basis_points,
+            },
+        };
+        let err = entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("jeanluc", &[]),
+            set_msg(500),
+        )
+            .unwrap_err();
+        assert_eq!(err, ContractError::Base(cw721_base::ContractError::Unauthorized {}));
+        let err =
+            entry::execute(deps.as_mut(), mock_env(), info.clone(), set_msg(10_001)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RoyaltyTooHigh {
+                basis_points: 10_001,
+                max: 10_000,
+            }
+        );
+        entry::execute(deps.as_mut(), mock_env(), info.clone(), set_msg(550)).unwrap();
+
+        let query_msg = QueryMsg::Extension {
+            msg: Cw2981QueryMsg::DefaultRoyalty {},
+        };
+        let res: DefaultRoyaltyResponse =
+            from_binary(&entry::query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(
+            res.royalty,
+            Some(DefaultRoyalty {
+                payment_address: Addr::unchecked("starfleet"),
+                basis_points: 550,
+            })
+        );
+
+        // the plain token falls back to the default, rounding down
+        let res =
+            query_royalties_info(deps.as_ref(), "Voyager".to_string(), Uint128::new(1001)).unwrap();
+        assert_eq!(
+            res,
+            RoyaltiesInfoResponse {
+                address: "starfleet".to_string(),
+                royalty_amount: Uint128::new(55),
+            }
+        );
+
+        // the token with its own royalty keeps it
+        let res = query_royalties_info(deps.as_ref(), "Enterprise".to_string(), Uint128::new(1000))
+            .unwrap();
+        assert_eq!(
+            res,
+            RoyaltiesInfoResponse {
+                address: "jeanluc".to_string(),
+                royalty_amount: Uint128::new(100),
+            }
+        );
+
+        // removing the default leaves the plain token without royalties again
+        let exec_msg = ExecuteMsg::Extension {
+            msg: Cw2981ExecuteMsg::RemoveDefaultRoyalty {},
+        };
+        entry::execute(deps.as_mut(), mock_env(), info, exec_msg).unwrap();
+        let res =
+            query_royalties_info(deps.as_ref(), "Voyager".to_string(), Uint128::new(1000)).unwrap();
+        assert_eq!(res.royalty_amount, Uint128::zero());
+    }
 }
@@ -1,12 +1,30 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{CustomMsg, Uint128};
 
+use crate::state::DefaultRoyalty;
+
+#[cw_serde]
+pub enum Cw2981ExecuteMsg {
+    /// Set the royalty paid on tokens minted without a royalty_percentage.
+    /// Only the minter can set it, basis_points is out of 10000
+    SetDefaultRoyalty {
+        payment_address: String,
+        basis_points: u16,
+    },
+    /// Remove the collection default, after which only tokens with their
+    /// own royalty_percentage owe royalties
+    RemoveDefaultRoyalty {},
+}
+
+impl CustomMsg for Cw2981ExecuteMsg {}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum Cw2981QueryMsg {
     /// Should be called on sale to see if royalties are owed
     /// by the marketplace selling the NFT, if CheckRoyalties
-    /// returns true
+    /// returns true. Uses the token's own royalty if it has one,
+    /// else the collection default
     /// See https://eips.ethereum.org/EIPS/eip-2981
     #[returns(RoyaltiesInfoResponse)]
     RoyaltyInfo {
@@ -19,11 +37,13 @@ pub enum Cw2981QueryMsg {
     },
     /// Called against contract to determine if this NFT
     /// implements royalties. Should return a boolean as part of
-    /// CheckRoyaltiesResponse - default can simply be true
-    /// if royalties are implemented at token level
-    /// (i.e. always check on sale)
+    /// CheckRoyaltiesResponse - true once a default royalty is
+    /// set or any token was minted with its own royalty
     #[returns(CheckRoyaltiesResponse)]
     CheckRoyalties {},
+    /// The royalty used for tokens without their own, if any
+    #[returns(DefaultRoyaltyResponse)]
+    DefaultRoyalty {},
 }
 
 impl Default for Cw2981QueryMsg {
@@ -48,3 +68,8 @@ pub struct RoyaltiesInfoResponse {
 pub struct CheckRoyaltiesResponse {
     pub royalty_payments: bool,
 }
+
+#[cw_serde]
+pub struct DefaultRoyaltyResponse {
+    pub royalty: Option<DefaultRoyalty>,
+}
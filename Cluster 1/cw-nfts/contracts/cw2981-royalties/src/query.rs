@@ -1,8 +1,13 @@
 use cosmwasm_std::{Decimal, Deps, StdResult, Uint128};
 
-use crate::Cw2981Contract;
-use crate::msg::{CheckRoyaltiesResponse, RoyaltiesInfoResponse};
+use crate::{Cw2981Contract, Metadata};
+use crate::execute::MAX_BASIS_POINTS;
+use crate::msg::{CheckRoyaltiesResponse, DefaultRoyaltyResponse, RoyaltiesInfoResponse};
+use crate::state::{DEFAULT_ROYALTY, TOKEN_ROYALTIES};
 
+/// Royalties resolve token first: a token minted with a royalty_percentage pays that,
+/// otherwise the collection default applies, and with neither nothing is owed.
+///
 /// NOTE: default behaviour here is to round down
 /// EIP2981 specifies that the rounding behaviour is at the discretion of the implementer
 pub fn query_royalties_info(
@@ -13,36 +18,41 @@ pub fn query_royalties_info(
     let contract = Cw2981Contract::default();
     let token_info = contract.tokens.load(deps.storage, &token_id)?;
 
-    let royalty_percentage = match token_info.extension {
-        Some(ref ext) => match ext.royalty_percentage {
-            Some(percentage) => Decimal::percent(percentage),
-            None => Decimal::percent(0),
-        },
-        None => Decimal::percent(0),
-    };
-    let royalty_from_sale_price = sale_price * royalty_percentage;
+    if let Some(Metadata {
+        royalty_percentage: Some(percentage),
+        royalty_payment_address,
+        ..
+    }) = token_info.extension
+    {
+        return Ok(RoyaltiesInfoResponse {
+            address: royalty_payment_address.unwrap_or_default(),
+            royalty_amount: sale_price * Decimal::percent(percentage),
+        });
+    }
 
-    let royalty_address = match token_info.extension {
-        Some(ext) => match ext.royalty_payment_address {
-            Some(addr) => addr,
-            None => String::from(""),
-        },
-        None => String::from(""),
-    };
+    match DEFAULT_ROYALTY.may_load(deps.storage)? {
+        Some(royalty) => Ok(RoyaltiesInfoResponse {
+            address: royalty.payment_address.into_string(),
+            royalty_amount: sale_price.multiply_ratio(royalty.basis_points, MAX_BASIS_POINTS),
+        }),
+        None => Ok(RoyaltiesInfoResponse {
+            address: String::from(""),
+            royalty_amount: Uint128::zero(),
+        }),
+    }
+}
 
-    Ok(RoyaltiesInfoResponse {
-        address: royalty_address,
-        royalty_amount: royalty_from_sale_price,
-    })
+/// Royalties are owed once a collection default is set or any token has been
+/// minted with its own royalty. Burning that token does not reset this, so
+/// marketplaces may still be asked to check tokens that owe nothing.
+pub fn check_royalties(deps: Deps) -> StdResult<CheckRoyaltiesResponse> {
+    let royalty_payments = DEFAULT_ROYALTY.may_load(deps.storage)?.is_some()
+        || TOKEN_ROYALTIES.may_load(deps.storage)?.unwrap_or(true);
+    Ok(CheckRoyaltiesResponse { royalty_payments })
 }
 
-/// As our default implementation here specifies royalties at token level
-/// and not at contract level, it is therefore logically true that
-/// on sale, every token managed by this contract should be checked
-/// to see if royalties are owed, and to whom. If you are importing
-/// this logic, you may want a custom implementation here
-pub fn check_royalties(_deps: Deps) -> StdResult<CheckRoyaltiesResponse> {
-    Ok(CheckRoyaltiesResponse {
-        royalty_payments: true,
+pub fn query_default_royalty(deps: Deps) -> StdResult<DefaultRoyaltyResponse> {
+    Ok(DefaultRoyaltyResponse {
+        royalty: DEFAULT_ROYALTY.may_load(deps.storage)?,
     })
 }
@@ -0,0 +1,17 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+
+/// Royalty paid on every token that does not set its own royalty_percentage
+#[cw_serde]
+pub struct DefaultRoyalty {
+    pub payment_address: Addr,
+    /// Share of the sale price in basis points, where 10000 is the whole price
+    pub basis_points: u16,
+}
+
+pub const DEFAULT_ROYALTY: Item<DefaultRoyalty> = Item::new("default_royalty");
+
+/// Set once a token is minted with its own royalty. Contracts instantiated before this
+/// was tracked have no entry, and are assumed to have token royalties.
+pub const TOKEN_ROYALTIES: Item<bool> = Item::new("token_royalties");
@@ -140,7 +140,9 @@ interface Balances {
 }
 
 interface MintInfo {
-  readonly minter: string
+  readonly minter: string | null  // null once renounced
+  readonly pending_minter: string | null
+  readonly pending_expires: Expiration | null
   readonly cap?: string // decimal as string
 }
 
@@ -176,10 +178,11 @@ interface InitMsg {
   readonly name: string
   readonly symbol: string
   readonly minter: string
+  readonly max_supply?: number
 }
 // Better to use this interface?
 interface MintMsg {
-  readonly token_id: TokenId
+  readonly token_id?: TokenId  // assigned by the contract if omitted
   readonly owner: string
   readonly name: string
   readonly description?: string
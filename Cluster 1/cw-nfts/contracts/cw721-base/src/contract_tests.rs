@@ -1,15 +1,29 @@
 #![cfg(test)]
 
-use cosmwasm_std::{CosmosMsg, DepsMut, Empty, from_binary, Response, to_binary, WasmMsg};
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{
+    Addr, Api, Binary, CanonicalAddr, CosmosMsg, DepsMut, Empty, Env, from_binary, OwnedDeps,
+    RecoverPubkeyError, Response, StdError, StdResult, SubMsg, to_binary, VerificationError, WasmMsg,
+};
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
 use cw721::{
-    Approval, ApprovalResponse, ContractInfoResponse, Cw721Query, Cw721ReceiveMsg, Expiration,
-    NftInfoResponse, OperatorsResponse, OwnerOfResponse,
+    Approval, ApprovalResponse, ContractInfoResponse, Cw721HookMsg, Cw721Query, Cw721ReceiveMsg,
+    Expiration, NftInfoResponse, OperatorsResponse, OwnerDiff, OwnerOfResponse,
 };
+use cw_controllers::{HookError, HooksResponse};
+use k256::ecdsa::{Signature, SigningKey};
+use k256::ecdsa::signature::Signer;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
 
 use crate::{
-    ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, MintMsg, QueryMsg,
+    ContractError, Cw721Contract, Cw721HistoryQuery, Cw721StorageKeys, ExecuteMsg, Extension,
+    InstantiateMsg, MinterResponse, MintMsg, QueryMsg,
 };
+use crate::msg::{AllNftInfoResponse, Lock, LockResponse, MetadataFrozenResponse, TransferPolicy};
 
 const MINTER: &str = "merlin";
 const CONTRACT_NAME: &str = "Magic Power";
@@ -21,6 +35,8 @@ fn setup_contract(deps: DepsMut<'_>) -> Cw721Contract<'static, Extension, Empty,
         name: CONTRACT_NAME.to_string(),
         symbol: SYMBOL.to_string(),
         minter: String::from(MINTER),
+        max_supply: None,
+        transfer_policy: None,
     };
     let info = mock_info("creator", &[]);
     let res = contract.instantiate(deps, mock_env(), info, msg).unwrap();
@@ -37,6 +53,8 @@ fn proper_instantiation() {
         name: CONTRACT_NAME.to_string(),
         symbol: SYMBOL.to_string(),
         minter: String::from(MINTER),
+        max_supply: None,
+        transfer_policy: None,
     };
     let info = mock_info("creator", &[]);
 
@@ -48,7 +66,8 @@ fn proper_instantiation() {
 
     // it worked, let's query the state
     let res = contract.minter(deps.as_ref()).unwrap();
-    assert_eq!(MINTER, res.minter);
+    assert_eq!(Some(MINTER.to_string()), res.minter);
+    assert_eq!(None, res.pending_minter);
     let info = contract.contract_info(deps.as_ref()).unwrap();
     assert_eq!(
         info,
@@ -75,7 +94,7 @@ fn minting() {
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
 
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
+        token_id: Some(token_id.clone()),
         owner: String::from("medusa"),
         token_uri: Some(token_uri.clone()),
         extension: None,
@@ -127,7 +146,7 @@ fn minting() {
 
     // Cannot mint same token_id again
     let mint_msg2 = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
+        token_id: Some(token_id.clone()),
         owner: String::from("hercules"),
         token_uri: None,
         extension: None,
@@ -154,7 +173,7 @@ fn burning() {
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
 
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
+        token_id: Some(token_id.clone()),
         owner: MINTER.to_string(),
         token_uri: Some(token_uri),
         extension: None,
@@ -204,7 +223,7 @@ fn transferring_nft() {
     let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
 
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
+        token_id: Some(token_id.clone()),
         owner: String::from("venus"),
         token_uri: Some(token_uri),
         extension: None,
@@ -258,7 +277,7 @@ fn sending_nft() {
     let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
 
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
+        token_id: Some(token_id.clone()),
         owner: String::from("venus"),
         token_uri: Some(token_uri),
         extension: None,
@@ -324,7 +343,7 @@ fn approving_revoking() {
     let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
 
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
+        token_id: Some(token_id.clone()),
         owner: String::from("demeter"),
         token_uri: Some(token_uri),
         extension: None,
@@ -471,7 +490,7 @@ fn approving_all_revoking_all() {
     let token_uri2 = "https://www.merriam-webster.com/dictionary/grow2".to_string();
 
     let mint_msg1 = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id1.clone(),
+        token_id: Some(token_id1.clone()),
         owner: String::from("demeter"),
         token_uri: Some(token_uri1),
         extension: None,
@@ -483,7 +502,7 @@ fn approving_all_revoking_all() {
         .unwrap();
 
     let mint_msg2 = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id2.clone(),
+        token_id: Some(token_id2.clone()),
         owner: String::from("demeter"),
         token_uri: Some(token_uri2),
         extension: None,
@@ -687,7 +706,7 @@ fn query_tokens_by_owner() {
     let token_id3 = "sing".to_string();
 
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id1.clone(),
+        token_id: Some(token_id1.clone()),
         owner: demeter.clone(),
         token_uri: None,
         extension: None,
@@ -697,7 +716,7 @@ fn query_tokens_by_owner() {
         .unwrap();
 
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id2.clone(),
+        token_id: Some(token_id2.clone()),
         owner: ceres.clone(),
         token_uri: None,
         extension: None,
@@ -707,7 +726,7 @@ fn query_tokens_by_owner() {
         .unwrap();
 
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id3.clone(),
+        token_id: Some(token_id3.clone()),
         owner: demeter.clone(),
         token_uri: None,
         extension: None,
@@ -749,3 +768,1550 @@ fn query_tokens_by_owner() {
         .unwrap();
     assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
 }
+
+#[test]
+fn counting_tokens_by_owner() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let tokens = [("grow1", "demeter"), ("grow2", "ceres"), ("sing", "demeter")]
+        .iter()
+        .map(|(token_id, owner)| MintMsg::<Extension> {
+            token_id: Some(token_id.to_string()),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        })
+        .collect();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::BatchMint { tokens },
+        )
+        .unwrap();
+
+    let count = contract
+        .num_tokens_of(deps.as_ref(), "demeter".to_string())
+        .unwrap();
+    assert_eq!(2, count.count);
+    let count = contract
+        .num_tokens_of(deps.as_ref(), "persephone".to_string())
+        .unwrap();
+    assert_eq!(0, count.count);
+    let res = contract.all_owners(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.owners, vec!["ceres", "demeter"]);
+    let res = contract
+        .all_owners(deps.as_ref(), Some("ceres".to_string()), Some(1))
+        .unwrap();
+    assert_eq!(res.owners, vec!["demeter"]);
+
+    // an owner who hands over their last token is no longer listed
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("persephone"),
+        token_id: "grow2".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("ceres", &[]), transfer_msg)
+        .unwrap();
+    let res = contract.all_owners(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.owners, vec!["demeter", "persephone"]);
+
+    // nor is one who burns them all
+    let burn_msg = ExecuteMsg::BatchBurn {
+        token_ids: vec!["grow1".to_string(), "sing".to_string()],
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("demeter", &[]), burn_msg)
+        .unwrap();
+    let count = contract
+        .num_tokens_of(deps.as_ref(), "demeter".to_string())
+        .unwrap();
+    assert_eq!(0, count.count);
+    let res = contract.all_owners(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.owners, vec!["persephone"]);
+}
+
+#[test]
+fn transferring_minter() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // nothing to accept yet
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("arthur", &[]),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingMinter {});
+
+    // only the minter can propose a new one
+    let update_msg = ExecuteMsg::UpdateMinter {
+        new_minter: String::from("arthur"),
+        expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            update_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), update_msg)
+        .unwrap();
+
+    // the old minter stays in charge until the proposal is accepted
+    let res = contract.minter(deps.as_ref()).unwrap();
+    assert_eq!(
+        res,
+        MinterResponse {
+            minter: Some(MINTER.to_string()),
+            pending_minter: Some(String::from("arthur")),
+            pending_expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+        }
+    );
+
+    // only the proposed minter can accept, and only before it expires
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let mut late = mock_env();
+    late.block.height += 10;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            late,
+            mock_info("arthur", &[]),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MinterTransferExpired {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("arthur", &[]),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap();
+
+    let res = contract.minter(deps.as_ref()).unwrap();
+    assert_eq!(res.minter, Some(String::from("arthur")));
+    assert_eq!(res.pending_minter, None);
+
+    // the new minter can mint, the old one can't
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: Some("excalibur".to_string()),
+        owner: String::from("arthur"),
+        token_uri: None,
+        extension: None,
+    });
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            mint_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("arthur", &[]), mint_msg)
+        .unwrap();
+}
+
+#[test]
+fn renouncing_minter() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    let update_msg = ExecuteMsg::UpdateMinter {
+        new_minter: String::from("arthur"),
+        expires: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), update_msg)
+        .unwrap();
+
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::RenounceMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::RenounceMinter {},
+        )
+        .unwrap();
+
+    // there is no minter and the pending transfer is dropped
+    let res = contract.minter(deps.as_ref()).unwrap();
+    assert_eq!(
+        res,
+        MinterResponse {
+            minter: None,
+            pending_minter: None,
+            pending_expires: None,
+        }
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("arthur", &[]),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingMinter {});
+
+    // supply is frozen
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: Some("excalibur".to_string()),
+        owner: String::from("arthur"),
+        token_uri: None,
+        extension: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoMinter {});
+}
+
+#[test]
+fn batch_minting() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let tokens: Vec<_> = ["grow1", "grow2", "sing"]
+        .iter()
+        .map(|token_id| MintMsg::<Extension> {
+            token_id: Some(token_id.to_string()),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        })
+        .collect();
+    let batch_msg = ExecuteMsg::BatchMint {
+        tokens: tokens.clone(),
+    };
+
+    // random cannot mint, nor can anyone mint nothing
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            batch_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::BatchMint { tokens: vec![] },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::EmptyBatch {});
+
+    // every token is minted and reported, the count goes up by the batch size
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), batch_msg)
+        .unwrap();
+    let minted: Vec<_> = res
+        .attributes
+        .iter()
+        .filter(|attr| attr.key == "token_id")
+        .map(|attr| attr.value.as_str())
+        .collect();
+    assert_eq!(minted, vec!["grow1", "grow2", "sing"]);
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(3, count.count);
+
+    // a batch repeating a claimed id fails
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::BatchMint {
+                tokens: tokens[2..].to_vec(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Claimed {});
+}
+
+#[test]
+fn minting_with_max_supply_and_assigned_ids() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: Some(4),
+        transfer_policy: None,
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+    let minter = mock_info(MINTER, &[]);
+    let mint_msg = |token_id: Option<&str>| MintMsg::<Extension> {
+        token_id: token_id.map(String::from),
+        owner: String::from("hermes"),
+        token_uri: None,
+        extension: None,
+    };
+
+    // tokens minted without an id are numbered from 0, skipping ids already taken
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::Mint(mint_msg(Some("1"))),
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::Mint(mint_msg(None)),
+        )
+        .unwrap();
+    assert!(res.attributes.iter().any(|attr| attr.key == "token_id" && attr.value == "0"));
+
+    // batches skip the ids given explicitly in the same batch too
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::BatchMint {
+                tokens: vec![mint_msg(None), mint_msg(Some("2"))],
+            },
+        )
+        .unwrap();
+    let minted: Vec<_> = res
+        .attributes
+        .iter()
+        .filter(|attr| attr.key == "token_id")
+        .map(|attr| attr.value.as_str())
+        .collect();
+    assert_eq!(minted, vec!["3", "2"]);
+
+    // burning does not make room for more once the supply is used up
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hermes", &[]),
+            ExecuteMsg::Burn {
+                token_id: "0".to_string(),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::Mint(mint_msg(None)),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MaxSupplyReached { max_supply: 4 });
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::BatchMint {
+                tokens: vec![mint_msg(Some("4"))],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MaxSupplyReached { max_supply: 4 });
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(3, count.count);
+}
+
+#[test]
+fn batch_transferring_and_burning() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let tokens: Vec<_> = ["grow1", "grow2", "sing"]
+        .iter()
+        .map(|token_id| MintMsg::<Extension> {
+            token_id: Some(token_id.to_string()),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        })
+        .collect();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::BatchMint { tokens },
+        )
+        .unwrap();
+
+    // only tokens the sender can send may be in the batch
+    let transfer_msg = ExecuteMsg::BatchTransfer {
+        recipient: String::from("ceres"),
+        token_ids: vec!["grow1".to_string(), "grow2".to_string()],
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // an operator of the owner can move them all
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            ExecuteMsg::ApproveAll {
+                operator: String::from("random"),
+                expires: None,
+            },
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "batch_transfer")
+            .add_attribute("sender", "random")
+            .add_attribute("recipient", "ceres")
+            .add_attribute("token_id", "grow1")
+            .add_attribute("token_id", "grow2")
+    );
+    let tokens = contract
+        .tokens(deps.as_ref(), String::from("ceres"), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["grow1", "grow2"]);
+
+    // burning needs access to every token in the batch
+    let burn_msg = ExecuteMsg::BatchBurn {
+        token_ids: vec!["grow1".to_string(), "sing".to_string()],
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("ceres", &[]),
+            burn_msg,
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let burn_msg = ExecuteMsg::BatchBurn {
+        token_ids: vec!["grow1".to_string(), "grow2".to_string()],
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("ceres", &[]),
+            burn_msg,
+        )
+        .unwrap();
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(1, count.count);
+    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    assert_eq!(tokens.tokens, vec!["sing"]);
+
+    // naming a token twice is rejected
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            ExecuteMsg::BatchBurn {
+                token_ids: vec!["sing".to_string(), "sing".to_string()],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DuplicateToken {
+            token_id: "sing".to_string()
+        }
+    );
+}
+
+#[test]
+fn updating_and_freezing_metadata() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    for token_id in ["petrify", "enchant"] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: Some(token_id.to_string()),
+            owner: String::from("medusa"),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // only the minter can update metadata, not even the owner
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+    let update_msg = |token_id: &str| ExecuteMsg::UpdateNftInfo {
+        token_id: token_id.to_string(),
+        token_uri: Some(token_uri.clone()),
+        extension: None,
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("medusa", &[]),
+            update_msg("petrify"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), update_msg("petrify"))
+        .unwrap();
+    let info = contract
+        .nft_info(deps.as_ref(), "petrify".to_string())
+        .unwrap();
+    assert_eq!(info.token_uri, Some(token_uri.clone()));
+
+    // freezing one token leaves the others editable
+    let freeze_msg = ExecuteMsg::FreezeMetadata {
+        token_id: Some("petrify".to_string()),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("medusa", &[]),
+            freeze_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), freeze_msg)
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), update_msg("petrify"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::MetadataFrozen {});
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), update_msg("enchant"))
+        .unwrap();
+
+    let query_msg = QueryMsg::MetadataFrozen {
+        token_id: "petrify".to_string(),
+    };
+    let res: MetadataFrozenResponse =
+        from_binary(&contract.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+    assert!(res.frozen);
+    let res = contract
+        .metadata_frozen(deps.as_ref(), "enchant".to_string())
+        .unwrap();
+    assert!(!res.frozen);
+
+    // freezing the collection covers every token
+    let freeze_msg = ExecuteMsg::FreezeMetadata { token_id: None };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), freeze_msg)
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), update_msg("enchant"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::MetadataFrozen {});
+    // an unknown token is still reported as missing rather than frozen
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter, update_msg("unknown"))
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Std(StdError::NotFound { .. })));
+    let res = contract
+        .metadata_frozen(deps.as_ref(), "enchant".to_string())
+        .unwrap();
+    assert!(res.frozen);
+}
+
+#[test]
+fn enforcing_transfer_policies() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let tokens = ["torch", "lyre", "caduceus"]
+        .iter()
+        .map(|token_id| MintMsg::<Extension> {
+            token_id: Some(token_id.to_string()),
+            owner: String::from("apollo"),
+            token_uri: None,
+            extension: None,
+        })
+        .collect();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::BatchMint { tokens },
+        )
+        .unwrap();
+    let set_policy = |deps: DepsMut, policy: TransferPolicy| {
+        let msg = ExecuteMsg::UpdateTransferPolicy { policy };
+        contract.execute(deps, mock_env(), mock_info(MINTER, &[]), msg)
+    };
+    let transfer = |deps: DepsMut, env: Env, sender: &str, recipient: &str, token_id: &str| {
+        let msg = ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        };
+        contract.execute(deps, env, mock_info(sender, &[]), msg)
+    };
+
+    // only the minter sets the policy
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("apollo", &[]),
+            ExecuteMsg::UpdateTransferPolicy {
+                policy: TransferPolicy::Allow {},
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // denied tokens stay put but can still be burned
+    set_policy(deps.as_mut(), TransferPolicy::Deny {}).unwrap();
+    let res = contract.transfer_policy(deps.as_ref()).unwrap();
+    assert_eq!(res.policy, TransferPolicy::Deny {});
+    let err = transfer(deps.as_mut(), mock_env(), "apollo", "hermes", "torch").unwrap_err();
+    assert_eq!(err, ContractError::TransferDenied {});
+    let burn_msg = ExecuteMsg::Burn {
+        token_id: "torch".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("apollo", &[]), burn_msg)
+        .unwrap();
+
+    // the admin moves tokens it is approved for, the owner cannot
+    let policy = TransferPolicy::AdminOnly {
+        admin: String::from("zeus"),
+    };
+    set_policy(deps.as_mut(), policy).unwrap();
+    let err = transfer(deps.as_mut(), mock_env(), "apollo", "hermes", "lyre").unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let approve_msg = ExecuteMsg::ApproveAll {
+        operator: String::from("zeus"),
+        expires: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("apollo", &[]), approve_msg)
+        .unwrap();
+    transfer(deps.as_mut(), mock_env(), "zeus", "hermes", "lyre").unwrap();
+
+    // only listed recipients can receive
+    let policy = TransferPolicy::Allowlist {
+        recipients: vec![String::from("olympus")],
+    };
+    set_policy(deps.as_mut(), policy).unwrap();
+    let err = transfer(deps.as_mut(), mock_env(), "hermes", "apollo", "lyre").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RecipientNotAllowed {
+            recipient: String::from("apollo"),
+        }
+    );
+    let send_msg = ExecuteMsg::SendNft {
+        contract: String::from("olympus"),
+        token_id: "lyre".to_string(),
+        msg: to_binary("offering").unwrap(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("hermes", &[]), send_msg)
+        .unwrap();
+
+    // locked tokens move again once the lock expires, batches included
+    let expires = Expiration::AtHeight(mock_env().block.height + 10);
+    set_policy(deps.as_mut(), TransferPolicy::LockedUntil { expires }).unwrap();
+    let err = transfer(deps.as_mut(), mock_env(), "apollo", "hermes", "caduceus").unwrap_err();
+    assert_eq!(err, ContractError::TransferLocked { expires });
+    let batch_msg = ExecuteMsg::BatchTransfer {
+        recipient: String::from("hermes"),
+        token_ids: vec!["caduceus".to_string()],
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("apollo", &[]),
+            batch_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::TransferLocked { expires });
+    let mut env = mock_env();
+    env.block.height += 10;
+    contract
+        .execute(deps.as_mut(), env, mock_info("apollo", &[]), batch_msg)
+        .unwrap();
+}
+
+#[test]
+fn notifying_hooks() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let hook_msgs = |res: &Response, diffs: Vec<OwnerDiff>| {
+        let msg = Cw721HookMsg::new(diffs);
+        let expected: Vec<SubMsg> = ["staking", "rewards"]
+            .iter()
+            .map(|hook| SubMsg::new(msg.clone().into_cosmos_msg(*hook).unwrap()))
+            .collect();
+        assert_eq!(res.messages[..2], expected[..]);
+    };
+
+    // only the minter manages hooks, and each only once
+    let add_msg = |addr: &str| ExecuteMsg::AddHook {
+        addr: addr.to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("athena", &[]),
+            add_msg("staking"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    for hook in ["staking", "rewards"] {
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), add_msg(hook))
+            .unwrap();
+    }
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            add_msg("staking"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Hook(HookError::HookAlreadyRegistered {}));
+    let res: HooksResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::Hooks {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.hooks, vec!["staking", "rewards"]);
+
+    // minting, transferring, sending and burning all notify every hook
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: Some("aegis".to_string()),
+        owner: String::from("athena"),
+        token_uri: None,
+        extension: None,
+    });
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+    hook_msgs(
+        &res,
+        vec![OwnerDiff::new("aegis", None, Some("athena".to_string()))],
+    );
+
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("perseus"),
+        token_id: "aegis".to_string(),
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("athena", &[]), transfer_msg)
+        .unwrap();
+    hook_msgs(
+        &res,
+        vec![OwnerDiff::new(
+            "aegis",
+            Some("athena".to_string()),
+            Some("perseus".to_string()),
+        )],
+    );
+
+    let send_msg = ExecuteMsg::SendNft {
+        contract: String::from("parthenon"),
+        token_id: "aegis".to_string(),
+        msg: to_binary("dedication").unwrap(),
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("perseus", &[]), send_msg)
+        .unwrap();
+    hook_msgs(
+        &res,
+        vec![OwnerDiff::new(
+            "aegis",
+            Some("perseus".to_string()),
+            Some("parthenon".to_string()),
+        )],
+    );
+    // the receiver is called after the hooks
+    assert_eq!(res.messages.len(), 3);
+
+    let burn_msg = ExecuteMsg::Burn {
+        token_id: "aegis".to_string(),
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("parthenon", &[]), burn_msg)
+        .unwrap();
+    hook_msgs(
+        &res,
+        vec![OwnerDiff::new("aegis", Some("parthenon".to_string()), None)],
+    );
+
+    // batches send every diff in one message per hook
+    let tokens = ["spear", "helm"]
+        .iter()
+        .map(|token_id| MintMsg::<Extension> {
+            token_id: Some(token_id.to_string()),
+            owner: String::from("athena"),
+            token_uri: None,
+            extension: None,
+        })
+        .collect();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::BatchMint { tokens },
+        )
+        .unwrap();
+    hook_msgs(
+        &res,
+        vec![
+            OwnerDiff::new("spear", None, Some("athena".to_string())),
+            OwnerDiff::new("helm", None, Some("athena".to_string())),
+        ],
+    );
+
+    // removed hooks are no longer notified
+    for hook in ["staking", "rewards"] {
+        let remove_msg = ExecuteMsg::RemoveHook {
+            addr: hook.to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), remove_msg)
+            .unwrap();
+    }
+    let burn_msg = ExecuteMsg::BatchBurn {
+        token_ids: vec!["spear".to_string(), "helm".to_string()],
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("athena", &[]), burn_msg)
+        .unwrap();
+    assert!(res.messages.is_empty());
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::RemoveHook {
+                addr: String::from("staking"),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Hook(HookError::HookNotRegistered {}));
+}
+
+#[test]
+fn locking_tokens() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let tokens = ["trident", "chariot"]
+        .iter()
+        .map(|token_id| MintMsg::<Extension> {
+            token_id: Some(token_id.to_string()),
+            owner: String::from("poseidon"),
+            token_uri: None,
+            extension: None,
+        })
+        .collect();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::BatchMint { tokens },
+        )
+        .unwrap();
+    let lock_msg = |expires: Expiration| ExecuteMsg::Lock {
+        token_id: "trident".to_string(),
+        expires,
+    };
+    let expires = Expiration::AtHeight(mock_env().block.height + 10);
+
+    // only the owner approves a locker, and only the approved locker locks
+    let approve_msg = ExecuteMsg::ApproveLocker {
+        locker: String::from("stables"),
+        token_id: "trident".to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stables", &[]),
+            approve_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stables", &[]),
+            lock_msg(expires),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("poseidon", &[]),
+            approve_msg,
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stables", &[]),
+            lock_msg(Expiration::AtHeight(1)),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Expired {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stables", &[]),
+            lock_msg(expires),
+        )
+        .unwrap();
+
+    // the owner keeps the token but can't move or burn it
+    let res = contract
+        .owner_of(deps.as_ref(), mock_env(), "trident".to_string(), false)
+        .unwrap();
+    assert_eq!(res.owner, "poseidon");
+    let query_msg = QueryMsg::Lock {
+        token_id: "trident".to_string(),
+    };
+    let res: LockResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.lock,
+        Some(Lock {
+            locker: String::from("stables"),
+            expires,
+        })
+    );
+    let query_msg = QueryMsg::AllNftInfo {
+        token_id: "trident".to_string(),
+        include_expired: None,
+    };
+    let res: AllNftInfoResponse<Extension> = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.access.owner, "poseidon");
+    assert_eq!(
+        res.lock,
+        Some(Lock {
+            locker: String::from("stables"),
+            expires,
+        })
+    );
+    let res = contract
+        .all_nft_info_with_lock(deps.as_ref(), mock_env(), "chariot".to_string(), false)
+        .unwrap();
+    assert_eq!(res.lock, None);
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("amphitrite"),
+        token_id: "trident".to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("poseidon", &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Locked { expires });
+    let send_msg = ExecuteMsg::SendNft {
+        contract: String::from("atlantis"),
+        token_id: "trident".to_string(),
+        msg: to_binary("tribute").unwrap(),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("poseidon", &[]), send_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Locked { expires });
+    let burn_msg = ExecuteMsg::BatchBurn {
+        token_ids: vec!["chariot".to_string(), "trident".to_string()],
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("poseidon", &[]), burn_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Locked { expires });
+
+    // revoking the locker leaves its lock in place, the lock lifts once it expires
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("poseidon", &[]),
+            ExecuteMsg::RevokeLocker {
+                token_id: "trident".to_string(),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("poseidon", &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Locked { expires });
+    let mut env = mock_env();
+    env.block.height += 10;
+    let res = contract
+        .token_lock(deps.as_ref(), env.clone(), "trident".to_string())
+        .unwrap();
+    assert_eq!(res.lock, None);
+    let res = contract
+        .all_nft_info_with_lock(deps.as_ref(), env.clone(), "trident".to_string(), false)
+        .unwrap();
+    assert_eq!(res.lock, None);
+
+    // the locker can lift its lock early
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("poseidon", &[]),
+            ExecuteMsg::ApproveLocker {
+                locker: String::from("stables"),
+                token_id: "trident".to_string(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stables", &[]),
+            lock_msg(Expiration::Never {}),
+        )
+        .unwrap();
+    let unlock_msg = ExecuteMsg::Unlock {
+        token_id: "trident".to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("poseidon", &[]),
+            unlock_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("stables", &[]), unlock_msg)
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("poseidon", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+
+    // the new owner has to approve a locker again
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stables", &[]),
+            lock_msg(expires),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn recording_token_history() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let token_id = "astrolabe".to_string();
+    let env_at = |height: u64| {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    };
+
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: Some(token_id.clone()),
+        owner: String::from("ptolemy"),
+        token_uri: None,
+        extension: None,
+    });
+    contract
+        .execute(deps.as_mut(), env_at(10), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("kepler"),
+        token_id: token_id.clone(),
+    };
+    contract
+        .execute(deps.as_mut(), env_at(11), mock_info("ptolemy", &[]), transfer_msg)
+        .unwrap();
+    let send_msg = ExecuteMsg::SendNft {
+        contract: String::from("observatory"),
+        token_id: token_id.clone(),
+        msg: to_binary("look up").unwrap(),
+    };
+    contract
+        .execute(deps.as_mut(), env_at(12), mock_info("kepler", &[]), send_msg)
+        .unwrap();
+    let burn_msg = ExecuteMsg::Burn {
+        token_id: token_id.clone(),
+    };
+    contract
+        .execute(deps.as_mut(), env_at(13), mock_info("observatory", &[]), burn_msg)
+        .unwrap();
+
+    // every change is listed oldest first, and outlives the token
+    let res = contract
+        .token_history(deps.as_ref(), token_id.clone(), None, None)
+        .unwrap();
+    let changes: Vec<_> = res
+        .history
+        .iter()
+        .map(|change| (change.id, change.from.as_deref(), change.to.as_deref(), change.height))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            (0, None, Some("ptolemy"), 10),
+            (1, Some("ptolemy"), Some("kepler"), 11),
+            (2, Some("kepler"), Some("observatory"), 12),
+            (3, Some("observatory"), None, 13),
+        ]
+    );
+    assert_eq!(res.history[0].time, mock_env().block.time);
+
+    // paginate through it
+    let res = contract
+        .token_history(deps.as_ref(), token_id.clone(), None, Some(3))
+        .unwrap();
+    assert_eq!(res.history.len(), 3);
+    let res = contract
+        .token_history(deps.as_ref(), token_id, Some(res.history[2].id), Some(3))
+        .unwrap();
+    assert_eq!(res.history.len(), 1);
+    assert_eq!(res.history[0].id, 3);
+
+    // unknown tokens have no history
+    let res = contract
+        .token_history(deps.as_ref(), "armillary".to_string(), None, None)
+        .unwrap();
+    assert!(res.history.is_empty());
+}
+
+/// Humanizes 20 byte canonical addresses as hex, standing in for the bech32 account addresses
+/// chains derive from public keys, and handles every other address like MockApi
+#[derive(Copy, Clone, Default)]
+struct HexApi(MockApi);
+
+impl Api for HexApi {
+    fn addr_validate(&self, input: &str) -> StdResult<Addr> {
+        let canonical = self.addr_canonicalize(input)?;
+        self.addr_humanize(&canonical)
+    }
+
+    fn addr_canonicalize(&self, input: &str) -> StdResult<CanonicalAddr> {
+        match hex::decode(input) {
+            Ok(bytes) if bytes.len() == 20 => Ok(bytes.into()),
+            _ => self.0.addr_canonicalize(input),
+        }
+    }
+
+    fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        match canonical.len() {
+            20 => Ok(Addr::unchecked(hex::encode(canonical.as_slice()))),
+            _ => self.0.addr_humanize(canonical),
+        }
+    }
+
+    fn secp256k1_verify(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.0.secp256k1_verify(message_hash, signature, public_key)
+    }
+
+    fn secp256k1_recover_pubkey(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        recovery_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        self.0
+            .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+    }
+
+    fn ed25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.0.ed25519_verify(message, signature, public_key)
+    }
+
+    fn ed25519_batch_verify(
+        &self,
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        self.0.ed25519_batch_verify(messages, signatures, public_keys)
+    }
+
+    fn debug(&self, message: &str) {
+        self.0.debug(message)
+    }
+}
+
+#[test]
+fn permitting_approvals() {
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: HexApi::default(),
+        querier: MockQuerier::<Empty>::new(&[]),
+        custom_query_type: PhantomData,
+    };
+    let contract = setup_contract(deps.as_mut());
+    let key = SigningKey::from_bytes(&[7; 32]).unwrap();
+    let pubkey = Binary::from(key.verifying_key().to_bytes().as_slice());
+    let owner = hex::encode(Ripemd160::digest(Sha256::digest(pubkey.as_slice())));
+    // the permit JSON and the ADR-036 document wallets sign for it, built by hand
+    let sign = |action: &str, nonce: u64| {
+        let payload = format!(
+            r#"{{"chain_id":"{}","contract":"{}","action":{},"expires":null,"nonce":{}}}"#,
+            mock_env().block.chain_id,
+            mock_env().contract.address,
+            action,
+            nonce,
+        );
+        let doc = format!(
+            r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"sequence":"0"}}"#,
+            Binary::from(payload.as_bytes()).to_base64(),
+            owner,
+        );
+        let signature: Signature = key.sign(doc.as_bytes());
+        Binary::from(signature.as_ref())
+    };
+    let permit_msg = |spender: &str, token_id: &str, nonce: u64, signature: Binary| {
+        ExecuteMsg::Permit {
+            owner_pubkey: pubkey.clone(),
+            spender: spender.to_string(),
+            token_id: token_id.to_string(),
+            expires: None,
+            nonce,
+            signature,
+        }
+    };
+    for (token_id, token_owner) in [("owl", owner.as_str()), ("olive", "athena")] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: Some(token_id.to_string()),
+            owner: token_owner.to_string(),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+            .unwrap();
+    }
+
+    // anyone can submit the owner's signed permit
+    let approve_owl = r#"{"approve":{"spender":"hermes","token_id":"owl"}}"#;
+    let msg = permit_msg("hermes", "owl", 0, sign(approve_owl, 0));
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg.clone())
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", owner.as_str())
+            .add_attribute("spender", "hermes")
+            .add_attribute("token_id", "owl")
+            .add_attribute("permit_nonce", "0")
+    );
+    let res = contract
+        .approval(
+            deps.as_ref(),
+            mock_env(),
+            "owl".to_string(),
+            "hermes".to_string(),
+            false,
+        )
+        .unwrap();
+    assert_eq!(res.approval.spender, "hermes");
+
+    // a used permit can't be replayed
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidNonce { expected: 1 });
+    let res = contract.permit_nonce(deps.as_ref(), owner.clone()).unwrap();
+    assert_eq!(res.nonce, 1);
+
+    // the signature has to cover the submitted permit
+    let msg = permit_msg("thief", "owl", 1, sign(approve_owl, 1));
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSignature {});
+
+    // only compressed public keys are accepted
+    let uncompressed = key.verifying_key().to_encoded_point(false);
+    let msg = ExecuteMsg::Permit {
+        owner_pubkey: Binary::from(uncompressed.as_bytes()),
+        spender: "hermes".to_string(),
+        token_id: "owl".to_string(),
+        expires: None,
+        nonce: 1,
+        signature: sign(approve_owl, 1),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPubkey {});
+
+    // a permit grants no more than the owner could approve itself
+    let approve_olive = r#"{"approve":{"spender":"hermes","token_id":"olive"}}"#;
+    let msg = permit_msg("hermes", "olive", 1, sign(approve_olive, 1));
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // an operator needs its own PermitAll, signed as such
+    let permit_all = |signature: Binary| ExecuteMsg::PermitAll {
+        owner_pubkey: pubkey.clone(),
+        operator: "market".to_string(),
+        expires: None,
+        nonce: 1,
+        signature,
+    };
+    let approve_market = r#"{"approve":{"spender":"market","token_id":"owl"}}"#;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            permit_all(sign(approve_market, 1)),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSignature {});
+    let approve_all_market = r#"{"approve_all":{"operator":"market"}}"#;
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            permit_all(sign(approve_all_market, 1)),
+        )
+        .unwrap();
+    let res = contract
+        .operators(deps.as_ref(), mock_env(), owner.clone(), false, None, None)
+        .unwrap();
+    assert_eq!(res.operators.len(), 1);
+    assert_eq!(res.operators[0].spender, "market");
+    let res = contract.permit_nonce(deps.as_ref(), owner).unwrap();
+    assert_eq!(res.nonce, 2);
+}
+
+#[test]
+fn migrating() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: Some("scroll".to_string()),
+        owner: String::from("hermes"),
+        token_uri: None,
+        extension: None,
+    });
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+
+    // only the same contract can be upgraded, and never to an older version
+    let err = contract
+        .migrate(deps.as_mut(), mock_env(), "crates.io:cw20-base", "0.16.0")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongContract {
+            expected: "crates.io:cw20-base".to_string(),
+            previous: "crates.io:cw721-base".to_string(),
+        }
+    );
+    let err = contract
+        .migrate(deps.as_mut(), mock_env(), "crates.io:cw721-base", "0.15.0")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CannotDowngrade {
+            previous: env!("CARGO_PKG_VERSION").to_string(),
+            current: "0.15.0".to_string(),
+        }
+    );
+
+    // an older contract without owner counts or a minted total gets them on upgrade
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.15.0").unwrap();
+    contract.owner_counts.clear(deps.as_mut().storage);
+    contract.minted.remove(deps.as_mut().storage);
+    contract
+        .migrate(deps.as_mut(), mock_env(), "crates.io:cw721-base", "0.16.0")
+        .unwrap();
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, "0.16.0");
+    let count = contract
+        .num_tokens_of(deps.as_ref(), "hermes".to_string())
+        .unwrap();
+    assert_eq!(1, count.count);
+    let minted = contract.minted.load(deps.as_ref().storage).unwrap();
+    assert_eq!(1, minted);
+}
+
+#[test]
+fn moving_storage() {
+    let mut deps = mock_dependencies();
+    let previous = Cw721Contract::<Extension, Empty, Empty, Empty>::new(Cw721StorageKeys {
+        contract: "old_nft_info",
+        minter: "old_minter",
+        tokens: "old_tokens",
+        tokens_owner: "old_tokens__owner",
+        hooks: "old_hooks",
+        ..Cw721StorageKeys::default()
+    });
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: None,
+        transfer_policy: None,
+    };
+    previous
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+    let hook_msg = ExecuteMsg::AddHook {
+        addr: String::from("oracle"),
+    };
+    previous
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), hook_msg)
+        .unwrap();
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: Some("scroll".to_string()),
+        owner: String::from("hermes"),
+        token_uri: None,
+        extension: None,
+    });
+    previous
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+
+    // everything ends up under the current keys, the old keys are emptied
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    contract
+        .move_storage(deps.as_mut().storage, &previous)
+        .unwrap();
+    let info = contract.contract_info(deps.as_ref()).unwrap();
+    assert_eq!(info.name, CONTRACT_NAME);
+    let minter = contract.minter(deps.as_ref()).unwrap();
+    assert_eq!(minter.minter, Some(MINTER.to_string()));
+    let tokens = contract
+        .tokens(deps.as_ref(), "hermes".to_string(), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["scroll"]);
+    let tokens = previous.all_tokens(deps.as_ref(), None, None).unwrap();
+    assert!(tokens.tokens.is_empty());
+    assert!(previous.contract_info(deps.as_ref()).is_err());
+    let hooks = contract.hooks.query_hooks(deps.as_ref()).unwrap();
+    assert_eq!(hooks.hooks, vec!["oracle"]);
+    let hooks = previous.hooks.query_hooks(deps.as_ref()).unwrap();
+    assert!(hooks.hooks.is_empty());
+
+    // data under keys both layouts share stays where it is
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(1, count.count);
+    let res = contract
+        .token_history(deps.as_ref(), "scroll".to_string(), None, None)
+        .unwrap();
+    assert_eq!(res.history.len(), 1);
+}
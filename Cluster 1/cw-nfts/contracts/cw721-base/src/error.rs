@@ -1,4 +1,6 @@
 use cosmwasm_std::StdError;
+use cw721::Expiration;
+use cw_controllers::HookError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +8,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Hook(#[from] HookError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -17,4 +22,52 @@ pub enum ContractError {
 
     #[error("Approval not found for: {spender}")]
     ApprovalNotFound { spender: String },
+
+    #[error("Permit public key must be a compressed 33 byte secp256k1 key")]
+    InvalidPubkey {},
+
+    #[error("Permit signature is invalid")]
+    InvalidSignature {},
+
+    #[error("Permit nonce is invalid, expected {expected}")]
+    InvalidNonce { expected: u64 },
+
+    #[error("Max supply of {max_supply} tokens reached")]
+    MaxSupplyReached { max_supply: u64 },
+
+    #[error("Transfers are not allowed")]
+    TransferDenied {},
+
+    #[error("Transfers are locked until {expires}")]
+    TransferLocked { expires: Expiration },
+
+    #[error("Tokens cannot be sent to {recipient}")]
+    RecipientNotAllowed { recipient: String },
+
+    #[error("Token is locked until {expires}")]
+    Locked { expires: Expiration },
+
+    #[error("Metadata is frozen")]
+    MetadataFrozen {},
+
+    #[error("Batch is empty")]
+    EmptyBatch {},
+
+    #[error("Token {token_id} appears more than once in the batch")]
+    DuplicateToken { token_id: String },
+
+    #[error("Cannot migrate from {previous}, expected {expected}")]
+    WrongContract { expected: String, previous: String },
+
+    #[error("Cannot migrate from version {previous} to older version {current}")]
+    CannotDowngrade { previous: String, current: String },
+
+    #[error("Minter has been renounced")]
+    NoMinter {},
+
+    #[error("No minter transfer pending")]
+    NoPendingMinter {},
+
+    #[error("Minter transfer has expired")]
+    MinterTransferExpired {},
 }
@@ -1,16 +1,25 @@
-use cosmwasm_std::{Binary, CustomMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw2::set_contract_version;
-use cw721::{ContractInfoResponse, Cw721Execute, Cw721ReceiveMsg, Expiration};
+use cosmwasm_std::{
+    Addr, Api, Binary, CanonicalAddr, CustomMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult, Storage, SubMsg, to_binary,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw721::{
+    ContractInfoResponse, Cw721Execute, Cw721HookMsg, Cw721ReceiveMsg, Expiration, OwnerDiff,
+};
+use ripemd::Ripemd160;
 use serde::de::DeserializeOwned;
+use semver::Version;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg};
-use crate::state::{Approval, Cw721Contract, TokenInfo};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg, PermitAction, PermitPayload, TransferPolicy};
+use crate::state::{Approval, Cw721Contract, PendingMinter, TokenInfo, TokenLock};
 
 // Version info for migration
-const CONTRACT_NAME: &str = "crates.io:cw721-base";
-const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub(crate) const CONTRACT_NAME: &str = "crates.io:cw721-base";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
     where
@@ -35,9 +44,60 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         self.contract_info.save(deps.storage, &info)?;
         let minter = deps.api.addr_validate(&msg.minter)?;
         self.minter.save(deps.storage, &minter)?;
+        if let Some(max_supply) = msg.max_supply {
+            self.max_supply.save(deps.storage, &max_supply)?;
+        }
+        if let Some(policy) = msg.transfer_policy {
+            let policy = validate_transfer_policy(deps.as_ref(), policy)?;
+            self.transfer_policy.save(deps.storage, &policy)?;
+        }
         Ok(Response::default())
     }
 
+    /// Upgrades a contract instantiated under contract_name to contract_version, which derived
+    /// contracts pass in for themselves. State written by older releases is brought up to date,
+    /// storage keys that moved are handled separately by move_storage
+    pub fn migrate(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        contract_name: &str,
+        contract_version: &str,
+    ) -> Result<Response<C>, ContractError> {
+        let previous = get_contract_version(deps.storage)?;
+        if previous.contract != contract_name {
+            return Err(ContractError::WrongContract {
+                expected: contract_name.to_string(),
+                previous: previous.contract,
+            });
+        }
+        let parse = |version: &str| {
+            Version::parse(version).map_err(|err| StdError::generic_err(err.to_string()))
+        };
+        if parse(&previous.version)? > parse(contract_version)? {
+            return Err(ContractError::CannotDowngrade {
+                previous: previous.version,
+                current: contract_version.to_string(),
+            });
+        }
+
+        // contracts from before owner_counts existed have tokens but no counts
+        if self.owner_counts.is_empty(deps.storage) {
+            self.rebuild_owner_counts(deps.storage)?;
+        }
+        // nor a minted total, burned tokens are gone so the live tokens are the best estimate
+        if self.minted.may_load(deps.storage)?.is_none() {
+            let count = self.token_count(deps.storage)?;
+            self.minted.save(deps.storage, &count)?;
+        }
+        set_contract_version(deps.storage, contract_name, contract_version)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", previous.version)
+            .add_attribute("to_version", contract_version))
+    }
+
     pub fn execute(
         &self,
         deps: DepsMut,
@@ -59,6 +119,39 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
                 self.approve_all(deps, env, info, operator, expires)
             }
             ExecuteMsg::RevokeAll { operator } => self.revoke_all(deps, env, info, operator),
+            ExecuteMsg::Permit {
+                owner_pubkey,
+                spender,
+                token_id,
+                expires,
+                nonce,
+                signature,
+            } => {
+                let payload = PermitPayload {
+                    chain_id: env.block.chain_id.clone(),
+                    contract: env.contract.address.to_string(),
+                    action: PermitAction::Approve { spender, token_id },
+                    expires,
+                    nonce,
+                };
+                self.permit(deps, env, info, owner_pubkey, payload, signature)
+            }
+            ExecuteMsg::PermitAll {
+                owner_pubkey,
+                operator,
+                expires,
+                nonce,
+                signature,
+            } => {
+                let payload = PermitPayload {
+                    chain_id: env.block.chain_id.clone(),
+                    contract: env.contract.address.to_string(),
+                    action: PermitAction::ApproveAll { operator },
+                    expires,
+                    nonce,
+                };
+                self.permit(deps, env, info, owner_pubkey, payload, signature)
+            }
             ExecuteMsg::TransferNft {
                 recipient,
                 token_id,
@@ -69,6 +162,37 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
                 msg,
             } => self.send_nft(deps, env, info, contract, token_id, msg),
             ExecuteMsg::Burn { token_id } => self.burn(deps, env, info, token_id),
+            ExecuteMsg::ApproveLocker { locker, token_id } => {
+                self.approve_locker(deps, env, info, locker, token_id)
+            }
+            ExecuteMsg::RevokeLocker { token_id } => self.revoke_locker(deps, env, info, token_id),
+            ExecuteMsg::Lock { token_id, expires } => self.lock(deps, env, info, token_id, expires),
+            ExecuteMsg::Unlock { token_id } => self.unlock(deps, env, info, token_id),
+            ExecuteMsg::BatchMint { tokens } => self.batch_mint(deps, env, info, tokens),
+            ExecuteMsg::BatchTransfer {
+                recipient,
+                token_ids,
+            } => self.batch_transfer(deps, env, info, recipient, token_ids),
+            ExecuteMsg::BatchBurn { token_ids } => self.batch_burn(deps, env, info, token_ids),
+            ExecuteMsg::UpdateNftInfo {
+                token_id,
+                token_uri,
+                extension,
+            } => self.update_nft_info(deps, env, info, token_id, token_uri, extension),
+            ExecuteMsg::FreezeMetadata { token_id } => {
+                self.freeze_metadata(deps, env, info, token_id)
+            }
+            ExecuteMsg::UpdateTransferPolicy { policy } => {
+                self.update_transfer_policy(deps, env, info, policy)
+            }
+            ExecuteMsg::AddHook { addr } => self.add_hook(deps, env, info, addr),
+            ExecuteMsg::RemoveHook { addr } => self.remove_hook(deps, env, info, addr),
+            ExecuteMsg::UpdateMinter {
+                new_minter,
+                expires,
+            } => self.update_minter(deps, env, info, new_minter, expires),
+            ExecuteMsg::AcceptMinter {} => self.accept_minter(deps, env, info),
+            ExecuteMsg::RenounceMinter {} => self.renounce_minter(deps, env, info),
             ExecuteMsg::Extension { msg: _ } => Ok(Response::default()),
         }
     }
@@ -84,37 +208,449 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
 {
     pub fn mint(
         &self,
-        deps: DepsMut,
-        _env: Env,
+        mut deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         msg: MintMsg<T>,
     ) -> Result<Response<C>, ContractError> {
-        let minter = self.minter.load(deps.storage)?;
+        self.check_minter(deps.storage, &info.sender)?;
+
+        self.add_minted(deps.storage, 1)?;
+        let token_id = match &msg.token_id {
+            Some(token_id) => token_id.clone(),
+            None => self.next_token_id(deps.storage, &BTreeSet::new())?,
+        };
+        let owner = msg.owner.clone();
+        let diff = self._mint(deps.branch(), &env, &token_id, msg)?;
+        self.increment_tokens(deps.storage)?;
+
+        Ok(Response::new()
+            .add_submessages(self.prepare_hooks(deps.as_ref(), vec![diff])?)
+            .add_attribute("action", "mint")
+            .add_attribute("minter", info.sender)
+            .add_attribute("owner", owner)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn batch_mint(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        tokens: Vec<MintMsg<T>>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_minter(deps.storage, &info.sender)?;
+        if tokens.is_empty() {
+            return Err(ContractError::EmptyBatch {});
+        }
+        // ids given explicitly, which the assigned ones below must not reuse
+        let mut taken = BTreeSet::new();
+        for msg in &tokens {
+            deps.api.addr_validate(&msg.owner)?;
+            if let Some(token_id) = &msg.token_id {
+                if !taken.insert(token_id.clone()) {
+                    return Err(ContractError::DuplicateToken {
+                        token_id: token_id.clone(),
+                    });
+                }
+                if self.tokens.has(deps.storage, token_id) {
+                    return Err(ContractError::Claimed {});
+                }
+            }
+        }
+        let minted = tokens.len() as u64;
+        self.add_minted(deps.storage, minted)?;
+
+        let mut res = Response::new()
+            .add_attribute("action", "batch_mint")
+            .add_attribute("minter", info.sender);
+        let mut diffs = Vec::with_capacity(tokens.len());
+        for msg in tokens {
+            let token_id = match &msg.token_id {
+                Some(token_id) => token_id.clone(),
+                None => self.next_token_id(deps.storage, &taken)?,
+            };
+            res = res
+                .add_attribute("owner", msg.owner.clone())
+                .add_attribute("token_id", token_id.clone());
+            diffs.push(self._mint(deps.branch(), &env, &token_id, msg)?);
+        }
+        let count = self.token_count(deps.storage)? + minted;
+        self.token_count.save(deps.storage, &count)?;
+
+        Ok(res.add_submessages(self.prepare_hooks(deps.as_ref(), diffs)?))
+    }
+
+    pub fn batch_transfer(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: String,
+        token_ids: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_can_send_all(deps.as_ref(), &env, &info, &token_ids)?;
+        deps.api.addr_validate(&recipient)?;
+
+        let diffs = token_ids
+            .iter()
+            .map(|token_id| self._transfer_nft(deps.branch(), &env, &info, &recipient, token_id))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Response::new()
+            .add_submessages(self.prepare_hooks(deps.as_ref(), diffs)?)
+            .add_attribute("action", "batch_transfer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", recipient)
+            .add_attributes(token_ids.into_iter().map(|id| ("token_id", id))))
+    }
+
+    pub fn batch_burn(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_ids: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_can_send_all(deps.as_ref(), &env, &info, &token_ids)?;
+
+        let diffs = token_ids
+            .iter()
+            .map(|token_id| self._burn(deps.branch(), &env, &info, token_id))
+            .collect::<Result<_, _>>()?;
+        let count = self.token_count(deps.storage)? - token_ids.len() as u64;
+        self.token_count.save(deps.storage, &count)?;
+
+        Ok(Response::new()
+            .add_submessages(self.prepare_hooks(deps.as_ref(), diffs)?)
+            .add_attribute("action", "batch_burn")
+            .add_attribute("sender", info.sender)
+            .add_attributes(token_ids.into_iter().map(|id| ("token_id", id))))
+    }
+
+    /// approves on behalf of the owner of owner_pubkey, with exactly the rights
+    /// the owner would have had sending Approve or ApproveAll itself
+    pub fn permit(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        _info: MessageInfo,
+        owner_pubkey: Binary,
+        payload: PermitPayload,
+        signature: Binary,
+    ) -> Result<Response<C>, ContractError> {
+        // cosmos accounts derive their address from the compressed key only
+        if owner_pubkey.len() != 33 || !matches!(owner_pubkey[0], 0x02 | 0x03) {
+            return Err(ContractError::InvalidPubkey {});
+        }
+        let owner = pubkey_to_addr(deps.api, &owner_pubkey)?;
+        let expected = self
+            .permit_nonces
+            .may_load(deps.storage, &owner)?
+            .unwrap_or_default();
+        if payload.nonce != expected {
+            return Err(ContractError::InvalidNonce { expected });
+        }
+        let hash = Sha256::digest(permit_sign_doc(&owner, &payload)?.as_slice());
+        let verified = deps
+            .api
+            .secp256k1_verify(&hash, &signature, &owner_pubkey)
+            .map_err(StdError::from)?;
+        if !verified {
+            return Err(ContractError::InvalidSignature {});
+        }
+
+        let owner_info = MessageInfo {
+            sender: owner.clone(),
+            funds: vec![],
+        };
+        let res = match payload.action {
+            PermitAction::Approve { spender, token_id } => self.approve(
+                deps.branch(),
+                env,
+                owner_info,
+                spender,
+                token_id,
+                payload.expires,
+            )?,
+            PermitAction::ApproveAll { operator } => self.approve_all(
+                deps.branch(),
+                env,
+                owner_info,
+                operator,
+                payload.expires,
+            )?,
+        };
+        self.permit_nonces
+            .save(deps.storage, &owner, &(payload.nonce + 1))?;
+
+        Ok(res.add_attribute("permit_nonce", payload.nonce.to_string()))
+    }
+
+    pub fn approve_locker(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        locker: String,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_approve(deps.as_ref(), &env, &info, &token)?;
+        let locker_addr = deps.api.addr_validate(&locker)?;
+        self.lockers.save(deps.storage, &token_id, &locker_addr)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_locker")
+            .add_attribute("sender", info.sender)
+            .add_attribute("locker", locker)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn revoke_locker(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_approve(deps.as_ref(), &env, &info, &token)?;
+        self.lockers.remove(deps.storage, &token_id);
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_locker")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
 
-        if info.sender != minter {
+    pub fn lock(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        expires: Expiration,
+    ) -> Result<Response<C>, ContractError> {
+        let locker = self.lockers.may_load(deps.storage, &token_id)?;
+        if locker.as_ref() != Some(&info.sender) {
             return Err(ContractError::Unauthorized {});
         }
+        // reject expired data as invalid
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+        // a locker can change its own lock, but not one another locker still holds
+        if let Some(lock) = self.locks.may_load(deps.storage, &token_id)? {
+            if lock.locker != info.sender && !lock.is_expired(&env.block) {
+                return Err(ContractError::Locked {
+                    expires: lock.expires,
+                });
+            }
+        }
 
-        // create the token
-        let token = TokenInfo {
-            owner: deps.api.addr_validate(&msg.owner)?,
-            approvals: vec![],
-            token_uri: msg.token_uri,
-            extension: msg.extension,
+        let lock = TokenLock {
+            locker: info.sender.clone(),
+            expires,
         };
-        self.tokens
-            .update(deps.storage, &msg.token_id, |old| match old {
-                Some(_) => Err(ContractError::Claimed {}),
-                None => Ok(token),
-            })?;
+        self.locks.save(deps.storage, &token_id, &lock)?;
 
-        self.increment_tokens(deps.storage)?;
+        Ok(Response::new()
+            .add_attribute("action", "lock")
+            .add_attribute("locker", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("expires", expires.to_string()))
+    }
+
+    pub fn unlock(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let lock = self.locks.load(deps.storage, &token_id)?;
+        if lock.locker != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        self.locks.remove(deps.storage, &token_id);
 
         Ok(Response::new()
-            .add_attribute("action", "mint")
+            .add_attribute("action", "unlock")
+            .add_attribute("locker", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn update_nft_info(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: String,
+        token_uri: Option<String>,
+        extension: T,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_minter(deps.storage, &info.sender)?;
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        if self.is_metadata_frozen(deps.storage, &token_id)? {
+            return Err(ContractError::MetadataFrozen {});
+        }
+
+        token.token_uri = token_uri;
+        token.extension = extension;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_nft_info")
             .add_attribute("minter", info.sender)
-            .add_attribute("owner", msg.owner)
-            .add_attribute("token_id", msg.token_id))
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn freeze_metadata(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: Option<String>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_minter(deps.storage, &info.sender)?;
+
+        let res = Response::new()
+            .add_attribute("action", "freeze_metadata")
+            .add_attribute("minter", info.sender);
+        match token_id {
+            Some(token_id) => {
+                // only existing tokens can be frozen, so a later mint can't inherit the flag
+                self.tokens.load(deps.storage, &token_id)?;
+                self.frozen_tokens.save(deps.storage, &token_id, &Empty {})?;
+                Ok(res.add_attribute("token_id", token_id))
+            }
+            None => {
+                self.metadata_frozen.save(deps.storage, &true)?;
+                Ok(res)
+            }
+        }
+    }
+
+    pub fn update_transfer_policy(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        policy: TransferPolicy,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_minter(deps.storage, &info.sender)?;
+        let policy = validate_transfer_policy(deps.as_ref(), policy)?;
+        self.transfer_policy.save(deps.storage, &policy)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_transfer_policy")
+            .add_attribute("minter", info.sender))
+    }
+
+    pub fn add_hook(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_minter(deps.storage, &info.sender)?;
+        let hook = deps.api.addr_validate(&addr)?;
+        self.hooks.add_hook(deps.storage, hook)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "add_hook")
+            .add_attribute("minter", info.sender)
+            .add_attribute("hook", addr))
+    }
+
+    pub fn remove_hook(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_minter(deps.storage, &info.sender)?;
+        let hook = deps.api.addr_validate(&addr)?;
+        self.hooks.remove_hook(deps.storage, hook)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "remove_hook")
+            .add_attribute("minter", info.sender)
+            .add_attribute("hook", addr))
+    }
+
+    pub fn update_minter(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        new_minter: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_minter(deps.storage, &info.sender)?;
+
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+
+        // a new proposal replaces any pending one
+        let pending = PendingMinter {
+            minter: deps.api.addr_validate(&new_minter)?,
+            expires,
+        };
+        self.pending_minter.save(deps.storage, &pending)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_minter")
+            .add_attribute("minter", info.sender)
+            .add_attribute("pending_minter", new_minter))
+    }
+
+    pub fn accept_minter(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        let pending = self
+            .pending_minter
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingMinter {})?;
+        if info.sender != pending.minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        if pending.expires.is_expired(&env.block) {
+            return Err(ContractError::MinterTransferExpired {});
+        }
+
+        self.minter.save(deps.storage, &pending.minter)?;
+        self.pending_minter.remove(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "accept_minter")
+            .add_attribute("minter", info.sender))
+    }
+
+    pub fn renounce_minter(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_minter(deps.storage, &info.sender)?;
+
+        // without a minter nothing can be minted, so the supply is frozen
+        self.minter.remove(deps.storage);
+        self.pending_minter.remove(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "renounce_minter")
+            .add_attribute("sender", info.sender))
     }
 }
 
@@ -129,15 +665,16 @@ impl<'a, T, C, E, Q> Cw721Execute<T, C> for Cw721Contract<'a, T, C, E, Q>
 
     fn transfer_nft(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         recipient: String,
         token_id: String,
     ) -> Result<Response<C>, ContractError> {
-        self._transfer_nft(deps, &env, &info, &recipient, &token_id)?;
+        let diff = self._transfer_nft(deps.branch(), &env, &info, &recipient, &token_id)?;
 
         Ok(Response::new()
+            .add_submessages(self.prepare_hooks(deps.as_ref(), vec![diff])?)
             .add_attribute("action", "transfer_nft")
             .add_attribute("sender", info.sender)
             .add_attribute("recipient", recipient)
@@ -146,7 +683,7 @@ impl<'a, T, C, E, Q> Cw721Execute<T, C> for Cw721Contract<'a, T, C, E, Q>
 
     fn send_nft(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         contract: String,
@@ -154,7 +691,7 @@ impl<'a, T, C, E, Q> Cw721Execute<T, C> for Cw721Contract<'a, T, C, E, Q>
         msg: Binary,
     ) -> Result<Response<C>, ContractError> {
         // Transfer token
-        self._transfer_nft(deps, &env, &info, &contract, &token_id)?;
+        let diff = self._transfer_nft(deps.branch(), &env, &info, &contract, &token_id)?;
 
         let send = Cw721ReceiveMsg {
             sender: info.sender.to_string(),
@@ -162,8 +699,9 @@ impl<'a, T, C, E, Q> Cw721Execute<T, C> for Cw721Contract<'a, T, C, E, Q>
             msg,
         };
 
-        // Send message
+        // Send message, hooks hear about the transfer before the receiver acts on it
         Ok(Response::new()
+            .add_submessages(self.prepare_hooks(deps.as_ref(), vec![diff])?)
             .add_message(send.into_cosmos_msg(contract.clone())?)
             .add_attribute("action", "send_nft")
             .add_attribute("sender", info.sender)
@@ -250,18 +788,16 @@ impl<'a, T, C, E, Q> Cw721Execute<T, C> for Cw721Contract<'a, T, C, E, Q>
 
     fn burn(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
     ) -> Result<Response<C>, ContractError> {
-        let token = self.tokens.load(deps.storage, &token_id)?;
-        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-
-        self.tokens.remove(deps.storage, &token_id)?;
+        let diff = self._burn(deps.branch(), &env, &info, &token_id)?;
         self.decrement_tokens(deps.storage)?;
 
         Ok(Response::new()
+            .add_submessages(self.prepare_hooks(deps.as_ref(), vec![diff])?)
             .add_attribute("action", "burn")
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
@@ -276,6 +812,77 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         E: CustomMsg,
         Q: CustomMsg,
 {
+    /// creates the token under token_id, which the caller resolves from msg.token_id,
+    /// without touching token_count, so batches can update it once
+    pub fn _mint(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        token_id: &str,
+        msg: MintMsg<T>,
+    ) -> Result<OwnerDiff, ContractError> {
+        let owner = deps.api.addr_validate(&msg.owner)?;
+        let token = TokenInfo {
+            owner: owner.clone(),
+            approvals: vec![],
+            token_uri: msg.token_uri,
+            extension: msg.extension,
+        };
+        self.tokens
+            .update(deps.storage, token_id, |old| match old {
+                Some(_) => Err(ContractError::Claimed {}),
+                None => Ok(token),
+            })?;
+        self.record_ownership(deps.storage, &env.block, token_id, None, Some(owner.clone()))?;
+        Ok(OwnerDiff::new(token_id, None, Some(owner.into_string())))
+    }
+
+    /// counts amount more tokens as minted, failing if that goes over max_supply
+    pub fn add_minted(&self, storage: &mut dyn Storage, amount: u64) -> Result<(), ContractError> {
+        let minted = self.minted.may_load(storage)?.unwrap_or_default() + amount;
+        if let Some(max_supply) = self.max_supply.may_load(storage)? {
+            if minted > max_supply {
+                return Err(ContractError::MaxSupplyReached { max_supply });
+            }
+        }
+        self.minted.save(storage, &minted)?;
+        Ok(())
+    }
+
+    /// hands out the lowest unused id from the counter on, skipping tokens minted
+    /// with an explicit id and any ids in taken
+    pub fn next_token_id(
+        &self,
+        storage: &mut dyn Storage,
+        taken: &BTreeSet<String>,
+    ) -> StdResult<String> {
+        let mut id = self.next_token_id.may_load(storage)?.unwrap_or_default();
+        while self.tokens.has(storage, &id.to_string()) || taken.contains(&id.to_string()) {
+            id += 1;
+        }
+        self.next_token_id.save(storage, &(id + 1))?;
+        Ok(id.to_string())
+    }
+
+    /// removes the token if the sender can send it, leaving token_count to the caller
+    pub fn _burn(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        token_id: &str,
+    ) -> Result<OwnerDiff, ContractError> {
+        let token = self.tokens.load(deps.storage, token_id)?;
+        self.check_can_send(deps.as_ref(), env, info, &token)?;
+        self.check_unlocked(deps.storage, env, token_id)?;
+        self.tokens.remove(deps.storage, token_id)?;
+        self.frozen_tokens.remove(deps.storage, token_id);
+        self.lockers.remove(deps.storage, token_id);
+        self.locks.remove(deps.storage, token_id);
+        self.record_ownership(deps.storage, &env.block, token_id, Some(token.owner.clone()), None)?;
+        Ok(OwnerDiff::new(token_id, Some(token.owner.into_string()), None))
+    }
+
     pub fn _transfer_nft(
         &self,
         deps: DepsMut,
@@ -283,15 +890,43 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         info: &MessageInfo,
         recipient: &str,
         token_id: &str,
-    ) -> Result<TokenInfo<T>, ContractError> {
+    ) -> Result<OwnerDiff, ContractError> {
         let mut token = self.tokens.load(deps.storage, token_id)?;
         // ensure we have permissions
         self.check_can_send(deps.as_ref(), env, info, &token)?;
-        // set owner and remove existing approvals
-        token.owner = deps.api.addr_validate(recipient)?;
+        self.check_unlocked(deps.storage, env, token_id)?;
+        let recipient = deps.api.addr_validate(recipient)?;
+        self.check_transfer_policy(deps.storage, env, info, &recipient)?;
+        // set owner and remove existing approvals, the locker was approved by the old owner too
+        let previous = token.owner;
+        token.owner = recipient;
         token.approvals = vec![];
         self.tokens.save(deps.storage, token_id, &token)?;
-        Ok(token)
+        self.lockers.remove(deps.storage, token_id);
+        self.locks.remove(deps.storage, token_id);
+        self.record_ownership(
+            deps.storage,
+            &env.block,
+            token_id,
+            Some(previous.clone()),
+            Some(token.owner.clone()),
+        )?;
+        Ok(OwnerDiff::new(
+            token_id,
+            Some(previous.into_string()),
+            Some(token.owner.into_string()),
+        ))
+    }
+
+    /// sends the diffs to every registered hook as a single Cw721HookMsg
+    pub fn prepare_hooks(&self, deps: Deps, diffs: Vec<OwnerDiff>) -> StdResult<Vec<SubMsg<C>>> {
+        let msg = Cw721HookMsg::new(diffs);
+        self.hooks
+            .query_hooks(deps)?
+            .hooks
+            .into_iter()
+            .map(|hook| Ok(SubMsg::new(msg.clone().into_cosmos_msg(hook)?)))
+            .collect()
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -333,6 +968,48 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         Ok(token)
     }
 
+    /// returns an error unless the sender is the current minter
+    pub fn check_minter(&self, storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+        match self.minter.may_load(storage)? {
+            Some(minter) if minter == *sender => Ok(()),
+            Some(_) => Err(ContractError::Unauthorized {}),
+            None => Err(ContractError::NoMinter {}),
+        }
+    }
+
+    /// checks the sender can send every token in a batch before any of them is changed,
+    /// so a single bad item leaves the whole batch untouched
+    pub fn check_can_send_all(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token_ids: &[String],
+    ) -> Result<(), ContractError> {
+        check_batch(token_ids.iter().map(String::as_str))?;
+        for token_id in token_ids {
+            let token = self.tokens.load(deps.storage, token_id)?;
+            self.check_can_send(deps, env, info, &token)?;
+            self.check_unlocked(deps.storage, env, token_id)?;
+        }
+        Ok(())
+    }
+
+    /// returns an error while the token holds a lock that has not expired
+    pub fn check_unlocked(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        token_id: &str,
+    ) -> Result<(), ContractError> {
+        match self.locks.may_load(storage, token_id)? {
+            Some(lock) if !lock.is_expired(&env.block) => Err(ContractError::Locked {
+                expires: lock.expires,
+            }),
+            _ => Ok(()),
+        }
+    }
+
     /// returns true iff the sender can execute approve or reject on the contract
     pub fn check_can_approve(
         &self,
@@ -361,6 +1038,44 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         }
     }
 
+    /// returns an error unless the transfer policy lets the sender move tokens to recipient
+    pub fn check_transfer_policy(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        info: &MessageInfo,
+        recipient: &Addr,
+    ) -> Result<(), ContractError> {
+        let policy = self.transfer_policy.may_load(storage)?;
+        match policy.unwrap_or(TransferPolicy::Allow {}) {
+            TransferPolicy::Allow {} => Ok(()),
+            TransferPolicy::Deny {} => Err(ContractError::TransferDenied {}),
+            TransferPolicy::AdminOnly { admin } => {
+                if info.sender == admin {
+                    Ok(())
+                } else {
+                    Err(ContractError::Unauthorized {})
+                }
+            }
+            TransferPolicy::Allowlist { recipients } => {
+                if recipients.iter().any(|allowed| recipient == allowed) {
+                    Ok(())
+                } else {
+                    Err(ContractError::RecipientNotAllowed {
+                        recipient: recipient.to_string(),
+                    })
+                }
+            }
+            TransferPolicy::LockedUntil { expires } => {
+                if expires.is_expired(&env.block) {
+                    Ok(())
+                } else {
+                    Err(ContractError::TransferLocked { expires })
+                }
+            }
+        }
+    }
+
     /// returns true iff the sender can transfer ownership of the token
     pub fn check_can_send(
         &self,
@@ -399,3 +1114,91 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         }
     }
 }
+
+/// validates the addresses in the policy, storing them the way the api normalizes them
+fn validate_transfer_policy(deps: Deps, policy: TransferPolicy) -> StdResult<TransferPolicy> {
+    Ok(match policy {
+        TransferPolicy::AdminOnly { admin } => TransferPolicy::AdminOnly {
+            admin: deps.api.addr_validate(&admin)?.into_string(),
+        },
+        TransferPolicy::Allowlist { recipients } => TransferPolicy::Allowlist {
+            recipients: recipients
+                .iter()
+                .map(|recipient| deps.api.addr_validate(recipient).map(Addr::into_string))
+                .collect::<StdResult<_>>()?,
+        },
+        policy => policy,
+    })
+}
+
+/// the account address of a secp256k1 public key, derived the way cosmos sdk chains do
+fn pubkey_to_addr(api: &dyn Api, pubkey: &[u8]) -> StdResult<Addr> {
+    let hash = Ripemd160::digest(Sha256::digest(pubkey));
+    api.addr_humanize(&CanonicalAddr::from(hash.as_slice()))
+}
+
+/// the ADR-036 document a wallet signs for signArbitrary(chain_id, signer, payload JSON),
+/// amino JSON with sorted keys and no whitespace
+fn permit_sign_doc(signer: &Addr, payload: &PermitPayload) -> StdResult<Binary> {
+    to_binary(&SignDoc {
+        account_number: "0",
+        chain_id: "",
+        fee: SignFee {
+            amount: [],
+            gas: "0",
+        },
+        memo: "",
+        msgs: [SignMsg {
+            r#type: "sign/MsgSignData",
+            value: SignData {
+                data: to_binary(payload)?,
+                signer: signer.as_str(),
+            },
+        }],
+        sequence: "0",
+    })
+}
+
+#[derive(Serialize)]
+struct SignDoc<'b> {
+    account_number: &'b str,
+    chain_id: &'b str,
+    fee: SignFee<'b>,
+    memo: &'b str,
+    msgs: [SignMsg<'b>; 1],
+    sequence: &'b str,
+}
+
+#[derive(Serialize)]
+struct SignFee<'b> {
+    amount: [Empty; 0],
+    gas: &'b str,
+}
+
+#[derive(Serialize)]
+struct SignMsg<'b> {
+    r#type: &'b str,
+    value: SignData<'b>,
+}
+
+#[derive(Serialize)]
+struct SignData<'b> {
+    data: Binary,
+    signer: &'b str,
+}
+
+/// a batch must hold at least one token and name each token only once
+fn check_batch<'b>(token_ids: impl Iterator<Item=&'b str>) -> Result<(), ContractError> {
+    let mut seen = BTreeSet::new();
+    for token_id in token_ids {
+        if !seen.insert(token_id) {
+            return Err(ContractError::DuplicateToken {
+                token_id: token_id.to_string(),
+            });
+        }
+    }
+    if seen.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+    Ok(())
+}
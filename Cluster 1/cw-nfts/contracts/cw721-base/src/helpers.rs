@@ -3,14 +3,19 @@ use cosmwasm_std::{
     Addr, CosmosMsg, CustomMsg, QuerierWrapper, StdResult, to_binary, WasmMsg, WasmQuery,
 };
 use cw721::{
-    AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
-    NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
+    Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse, NftInfoResponse,
+    NumTokensResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
 };
+use cw_controllers::HooksResponse;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::marker::PhantomData;
 
 use crate::{ExecuteMsg, QueryMsg};
+use crate::msg::{
+    AllNftInfoResponse, Lock, LockResponse, OwnersResponse, PermitNonceResponse,
+    TokenHistoryResponse,
+};
 
 #[cw_serde]
 pub struct Cw721Contract<Q: CustomMsg, E: CustomMsg>(
@@ -176,6 +181,71 @@ impl<Q: CustomMsg, E: CustomMsg> Cw721Contract<Q, E> {
         self.query(querier, req)
     }
 
+    /// With enumerable extension
+    pub fn num_tokens_of<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        owner: T,
+    ) -> StdResult<u64> {
+        let req = QueryMsg::NumTokensOf {
+            owner: owner.into(),
+        };
+        let res: NumTokensResponse = self.query(querier, req)?;
+        Ok(res.count)
+    }
+
+    /// With enumerable extension
+    pub fn all_owners(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OwnersResponse> {
+        let req = QueryMsg::AllOwners { start_after, limit };
+        self.query(querier, req)
+    }
+
+    /// With provenance extension
+    pub fn token_history<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: T,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<TokenHistoryResponse> {
+        let req = QueryMsg::TokenHistory {
+            token_id: token_id.into(),
+            start_after,
+            limit,
+        };
+        self.query(querier, req)
+    }
+
+    /// lock keeping the token in place, if any
+    pub fn lock<T: Into<String>>(&self, querier: &QuerierWrapper, token_id: T) -> StdResult<Option<Lock>> {
+        let req = QueryMsg::Lock {
+            token_id: token_id.into(),
+        };
+        let res: LockResponse = self.query(querier, req)?;
+        Ok(res.lock)
+    }
+
+    /// nonce the owner's next permit has to use
+    pub fn permit_nonce<T: Into<String>>(&self, querier: &QuerierWrapper, owner: T) -> StdResult<u64> {
+        let req = QueryMsg::PermitNonce {
+            owner: owner.into(),
+        };
+        let res: PermitNonceResponse = self.query(querier, req)?;
+        Ok(res.nonce)
+    }
+
+    /// contracts notified of ownership changes
+    pub fn hooks(&self, querier: &QuerierWrapper) -> StdResult<Vec<String>> {
+        let req = QueryMsg::Hooks {};
+        let res: HooksResponse = self.query(querier, req)?;
+        Ok(res.hooks)
+    }
+
     /// returns true if the contract supports the metadata extension
     pub fn has_metadata(&self, querier: &QuerierWrapper) -> bool {
         self.contract_info(querier).is_ok()
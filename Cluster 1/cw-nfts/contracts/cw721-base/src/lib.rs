@@ -1,8 +1,9 @@
 use cosmwasm_std::Empty;
 
 pub use crate::error::ContractError;
-pub use crate::msg::{ExecuteMsg, InstantiateMsg, MinterResponse, MintMsg, QueryMsg};
-pub use crate::state::Cw721Contract;
+pub use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, MinterResponse, MintMsg, QueryMsg};
+pub use crate::query::Cw721HistoryQuery;
+pub use crate::state::{Cw721Contract, Cw721StorageKeys};
 
 mod contract_tests;
 mod error;
@@ -11,6 +12,7 @@ pub mod helpers;
 pub mod msg;
 mod query;
 pub mod state;
+mod upgrades;
 
 // This is a simple type to let us handle empty extensions
 pub type Extension = Option<Empty>;
@@ -45,6 +47,12 @@ pub mod entry {
         tract.execute(deps, env, info, msg)
     }
 
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+        let tract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+        tract.migrate(deps, env, execute::CONTRACT_NAME, execute::CONTRACT_VERSION)
+    }
+
     #[cfg_attr(not(feature = "library"), entry_point)]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg<Empty>) -> StdResult<Binary> {
         let tract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
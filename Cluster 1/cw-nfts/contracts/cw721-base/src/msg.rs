@@ -1,6 +1,6 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Binary;
-use cw721::Expiration;
+use cosmwasm_std::{Binary, Timestamp};
+use cw721::{Expiration, NftInfoResponse, OwnerOfResponse};
 use schemars::JsonSchema;
 
 #[cw_serde]
@@ -14,6 +14,30 @@ pub struct InstantiateMsg {
     /// This is designed for a base NFT that is controlled by an external program
     /// or contract. You will likely replace this with custom logic in custom NFTs
     pub minter: String,
+
+    /// Optional cap on how many tokens can ever be minted. Burning a token
+    /// does not free up room for another one
+    pub max_supply: Option<u64>,
+
+    /// Restricts who may transfer or send tokens, unset allows everyone the usual
+    /// owner, approval and operator checks let through
+    pub transfer_policy: Option<TransferPolicy>,
+}
+
+/// Checked on every transfer and send, on top of the owner, approval and operator checks.
+/// Minting and burning are never restricted
+#[cw_serde]
+pub enum TransferPolicy {
+    /// No restrictions
+    Allow {},
+    /// Tokens never move once minted, e.g. soulbound tokens
+    Deny {},
+    /// Only the admin may transfer or send, and only tokens it could move anyway
+    AdminOnly { admin: String },
+    /// Tokens can only be transferred or sent to these addresses
+    Allowlist { recipients: Vec<String> },
+    /// No transfers or sends until the expiration
+    LockedUntil { expires: Expiration },
 }
 
 /// This is like Cw721ExecuteMsg but we add a Mint command for an owner
@@ -47,6 +71,30 @@ pub enum ExecuteMsg<T, E> {
     },
     /// Remove previously granted ApproveAll permission
     RevokeAll { operator: String },
+    /// Approve on behalf of the owner of owner_pubkey, who signed the matching PermitPayload
+    /// off-chain. Anyone can submit it, each nonce works only once
+    Permit {
+        /// Compressed 33 byte secp256k1 public key of the owner's account
+        owner_pubkey: Binary,
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+        /// Must equal the owner's PermitNonce
+        nonce: u64,
+        /// ADR-036 signature of the PermitPayload JSON, see PermitPayload
+        signature: Binary,
+    },
+    /// ApproveAll on behalf of the owner of owner_pubkey, works like Permit
+    PermitAll {
+        /// Compressed 33 byte secp256k1 public key of the owner's account
+        owner_pubkey: Binary,
+        operator: String,
+        expires: Option<Expiration>,
+        /// Must equal the owner's PermitNonce
+        nonce: u64,
+        /// ADR-036 signature of the PermitPayload JSON, see PermitPayload
+        signature: Binary,
+    },
 
     /// Mint a new NFT, can only be called by the contract minter
     Mint(MintMsg<T>),
@@ -54,14 +102,101 @@ pub enum ExecuteMsg<T, E> {
     /// Burn an NFT the sender has access to
     Burn { token_id: String },
 
+    /// Allow locker, e.g. a staking contract, to lock the token in place. Replaces any earlier
+    /// locker and can only be called by the owner or one of its operators
+    ApproveLocker { locker: String, token_id: String },
+    /// Take back ApproveLocker. A lock already set stays until it expires or is lifted
+    RevokeLocker { token_id: String },
+    /// Block transferring, sending and burning the token until expires while the owner
+    /// keeps it. Can only be called by the token's approved locker
+    Lock {
+        token_id: String,
+        expires: Expiration,
+    },
+    /// Lift the lock early, can only be called by the locker that set it
+    Unlock { token_id: String },
+
+    /// Mint several NFTs at once, can only be called by the contract minter.
+    /// Nothing is minted if any of them fails
+    BatchMint { tokens: Vec<MintMsg<T>> },
+    /// Transfer several NFTs the sender has access to to the same recipient.
+    /// Nothing is transferred if any of them fails
+    BatchTransfer {
+        recipient: String,
+        token_ids: Vec<String>,
+    },
+    /// Burn several NFTs the sender has access to.
+    /// Nothing is burned if any of them fails
+    BatchBurn { token_ids: Vec<String> },
+
+    /// Replace the token_uri and extension of an existing NFT, can only be called by the
+    /// contract minter while the token's metadata is not frozen
+    UpdateNftInfo {
+        token_id: String,
+        token_uri: Option<String>,
+        extension: T,
+    },
+    /// Permanently block UpdateNftInfo for one token, or for every token if token_id
+    /// is unset. Can only be called by the contract minter
+    FreezeMetadata { token_id: Option<String> },
+
+    /// Replace the transfer policy, can only be called by the contract minter
+    UpdateTransferPolicy { policy: TransferPolicy },
+
+    /// Notify addr of every mint, transfer and burn with a Cw721HookMsg,
+    /// can only be called by the contract minter
+    AddHook { addr: String },
+    /// Stop notifying addr, can only be called by the contract minter
+    RemoveHook { addr: String },
+
+    /// Propose a new minter, who only takes over once it accepts.
+    /// If expiration is set, the proposal can't be accepted after it
+    UpdateMinter {
+        new_minter: String,
+        expires: Option<Expiration>,
+    },
+    /// Accept a pending minter proposal, can only be called by the proposed minter
+    AcceptMinter {},
+    /// Give up the minter role for good, no more tokens can be minted afterwards
+    RenounceMinter {},
+
     /// Extension msg
     Extension { msg: E },
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
+/// What the owner signs to permit an approval. The chain and contract are taken from the
+/// executing environment, so a permit can't be replayed on another collection or chain.
+///
+/// The owner signs it with ADR-036 `signArbitrary(chain_id, owner, data)`, as Keplr and
+/// other cosmos wallets do, where data is this struct as compact JSON with its fields in
+/// the order below and unset fields as null, e.g.
+/// `{"chain_id":"juno-1","contract":"juno1...","action":{"approve":{"spender":"juno1...","token_id":"1"}},"expires":null,"nonce":0}`
+#[cw_serde]
+pub struct PermitPayload {
+    pub chain_id: String,
+    pub contract: String,
+    pub action: PermitAction,
+    pub expires: Option<Expiration>,
+    pub nonce: u64,
+}
+
+/// The approval a permit grants
+#[cw_serde]
+pub enum PermitAction {
+    /// Same as Approve
+    Approve { spender: String, token_id: String },
+    /// Same as ApproveAll, the operator can move every token of the owner
+    ApproveAll { operator: String },
+}
+
 #[cw_serde]
 pub struct MintMsg<T> {
-    /// Unique ID of the NFT
-    pub token_id: String,
+    /// Unique ID of the NFT. If omitted, the contract assigns the next
+    /// unused number, counting up from "0"
+    pub token_id: Option<String>,
     /// The owner of the newly minted NFT
     pub owner: String,
     /// Universal resource identifier for this NFT
@@ -119,8 +254,8 @@ pub enum QueryMsg<Q: JsonSchema> {
     NftInfo { token_id: String },
     /// With MetaData Extension.
     /// Returns the result of both `NftInfo` and `OwnerOf` as one query as an optimization
-    /// for clients
-    #[returns(cw721::AllNftInfoResponse < Q >)]
+    /// for clients, along with the token's lock
+    #[returns(AllNftInfoResponse<Q>)]
     AllNftInfo {
         token_id: String,
         /// unset or false will filter out expired approvals, you must set to true to see them
@@ -142,11 +277,50 @@ pub enum QueryMsg<Q: JsonSchema> {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// With Enumerable extension.
+    /// Number of tokens held by the given address, 0 if none
+    #[returns(cw721::NumTokensResponse)]
+    NumTokensOf { owner: String },
+    /// With Enumerable extension.
+    /// Requires pagination. Lists every address holding at least one token.
+    #[returns(OwnersResponse)]
+    AllOwners {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// With Provenance extension.
+    /// Requires pagination. Lists every change of ownership of the token, oldest first.
+    #[returns(TokenHistoryResponse)]
+    TokenHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 
     /// Return the minter
     #[returns(MinterResponse)]
     Minter {},
 
+    /// Whether the token's metadata can no longer be updated
+    #[returns(MetadataFrozenResponse)]
+    MetadataFrozen { token_id: String },
+
+    /// Return the lock keeping the token in place, if any
+    #[returns(LockResponse)]
+    Lock { token_id: String },
+
+    /// Return the transfer policy
+    #[returns(TransferPolicyResponse)]
+    TransferPolicy {},
+
+    /// Return the nonce the owner's next Permit has to use
+    #[returns(PermitNonceResponse)]
+    PermitNonce { owner: String },
+
+    /// Return the contracts notified of ownership changes
+    #[returns(cw_controllers::HooksResponse)]
+    Hooks {},
+
     /// Extension query
     #[returns(())]
     Extension { msg: Q },
@@ -155,5 +329,76 @@ pub enum QueryMsg<Q: JsonSchema> {
 /// Shows who can mint these tokens
 #[cw_serde]
 pub struct MinterResponse {
-    pub minter: String,
+    /// None once the minter has been renounced
+    pub minter: Option<String>,
+    /// Proposed by UpdateMinter but not accepted yet
+    pub pending_minter: Option<String>,
+    pub pending_expires: Option<Expiration>,
+}
+
+#[cw_serde]
+pub struct MetadataFrozenResponse {
+    /// true if the token or the whole collection is frozen
+    pub frozen: bool,
+}
+
+#[cw_serde]
+pub struct Lock {
+    /// Account that locked the token, only it can unlock it early
+    pub locker: String,
+    /// When the lock lifts by itself (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct LockResponse {
+    /// Set while the token is locked and can't be transferred, sent or burned
+    pub lock: Option<Lock>,
+}
+
+/// cw721::AllNftInfoResponse with the token's lock, which the spec response has no field for
+#[cw_serde]
+pub struct AllNftInfoResponse<T> {
+    /// Who can transfer the token
+    pub access: OwnerOfResponse,
+    /// Data on the token itself
+    pub info: NftInfoResponse<T>,
+    /// Set while the token is locked and can't be transferred, sent or burned
+    pub lock: Option<Lock>,
+}
+
+#[cw_serde]
+pub struct PermitNonceResponse {
+    /// 0 until the owner's first permit is used
+    pub nonce: u64,
+}
+
+#[cw_serde]
+pub struct TransferPolicyResponse {
+    pub policy: TransferPolicy,
+}
+
+#[cw_serde]
+pub struct OwnersResponse {
+    /// Contains all holders in lexicographical ordering
+    pub owners: Vec<String>,
+}
+
+#[cw_serde]
+pub struct OwnershipChange {
+    /// Position in the token's history, pass as `start_after` to page through it
+    pub id: u64,
+    /// Previous owner, unset when the token was minted
+    pub from: Option<String>,
+    /// New owner, unset when the token was burned
+    pub to: Option<String>,
+    /// Block height at which the change happened
+    pub height: u64,
+    /// Block time at which the change happened
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct TokenHistoryResponse {
+    pub history: Vec<OwnershipChange>,
 }
@@ -2,16 +2,19 @@ use cosmwasm_std::{
     Addr, Binary, BlockInfo, CustomMsg, Deps, Env, Order, StdError, StdResult, to_binary,
 };
 use cw721::{
-    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, ContractInfoResponse, Cw721Query,
-    Expiration, NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse,
-    TokensResponse,
+    ApprovalResponse, ApprovalsResponse, ContractInfoResponse, Cw721Query, Expiration,
+    NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
 };
 use cw_storage_plus::Bound;
 use cw_utils::maybe_addr;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::msg::{MinterResponse, QueryMsg};
+use crate::msg::{
+    AllNftInfoResponse, Lock, LockResponse, MetadataFrozenResponse, MinterResponse,
+    OwnersResponse, OwnershipChange, PermitNonceResponse, QueryMsg, TokenHistoryResponse,
+    TransferPolicy, TransferPolicyResponse,
+};
 use crate::state::{Approval, Cw721Contract, TokenInfo};
 
 const DEFAULT_LIMIT: u32 = 10;
@@ -192,9 +195,9 @@ impl<'a, T, C, E, Q> Cw721Query<T> for Cw721Contract<'a, T, C, E, Q>
         env: Env,
         token_id: String,
         include_expired: bool,
-    ) -> StdResult<AllNftInfoResponse<T>> {
+    ) -> StdResult<cw721::AllNftInfoResponse<T>> {
         let info = self.tokens.load(deps.storage, &token_id)?;
-        Ok(AllNftInfoResponse {
+        Ok(cw721::AllNftInfoResponse {
             access: OwnerOfResponse {
                 owner: info.owner.to_string(),
                 approvals: humanize_approvals(&env.block, &info, include_expired),
@@ -207,6 +210,54 @@ impl<'a, T, C, E, Q> Cw721Query<T> for Cw721Contract<'a, T, C, E, Q>
     }
 }
 
+/// Queries cw721-base answers beyond the cw721 spec
+pub trait Cw721HistoryQuery {
+    /// Every ownership change of token_id, oldest first, kept after the token is burned
+    fn token_history(
+        &self,
+        deps: Deps,
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<TokenHistoryResponse>;
+}
+
+impl<'a, T, C, E, Q> Cw721HistoryQuery for Cw721Contract<'a, T, C, E, Q>
+    where
+        T: Serialize + DeserializeOwned + Clone,
+        C: CustomMsg,
+        E: CustomMsg,
+        Q: CustomMsg,
+{
+    fn token_history(
+        &self,
+        deps: Deps,
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<TokenHistoryResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let history: StdResult<Vec<_>> = self
+            .token_history
+            .prefix(&token_id)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(id, change)| OwnershipChange {
+                    id,
+                    from: change.from.map(Addr::into_string),
+                    to: change.to.map(Addr::into_string),
+                    height: change.height,
+                    time: change.time,
+                })
+            })
+            .collect();
+        Ok(TokenHistoryResponse { history: history? })
+    }
+}
+
 impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
     where
         T: Serialize + DeserializeOwned + Clone,
@@ -215,15 +266,112 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         Q: CustomMsg,
 {
     pub fn minter(&self, deps: Deps) -> StdResult<MinterResponse> {
-        let minter_addr = self.minter.load(deps.storage)?;
+        let minter = self.minter.may_load(deps.storage)?;
+        let pending = self.pending_minter.may_load(deps.storage)?;
         Ok(MinterResponse {
-            minter: minter_addr.to_string(),
+            minter: minter.map(|addr| addr.to_string()),
+            pending_minter: pending.as_ref().map(|p| p.minter.to_string()),
+            pending_expires: pending.map(|p| p.expires),
+        })
+    }
+
+    pub fn metadata_frozen(
+        &self,
+        deps: Deps,
+        token_id: String,
+    ) -> StdResult<MetadataFrozenResponse> {
+        // errors for unknown tokens
+        self.tokens.load(deps.storage, &token_id)?;
+        Ok(MetadataFrozenResponse {
+            frozen: self.is_metadata_frozen(deps.storage, &token_id)?,
+        })
+    }
+
+    pub fn token_lock(&self, deps: Deps, env: Env, token_id: String) -> StdResult<LockResponse> {
+        // errors for unknown tokens
+        self.tokens.load(deps.storage, &token_id)?;
+        let lock = self
+            .locks
+            .may_load(deps.storage, &token_id)?
+            .filter(|lock| !lock.is_expired(&env.block))
+            .map(|lock| Lock {
+                locker: lock.locker.into_string(),
+                expires: lock.expires,
+            });
+        Ok(LockResponse { lock })
+    }
+
+    /// all_nft_info along with the token's lock
+    pub fn all_nft_info_with_lock(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+        include_expired: bool,
+    ) -> StdResult<AllNftInfoResponse<T>> {
+        let lock = self.token_lock(deps, env.clone(), token_id.clone())?.lock;
+        let res = self.all_nft_info(deps, env, token_id, include_expired)?;
+        Ok(AllNftInfoResponse {
+            access: res.access,
+            info: res.info,
+            lock,
         })
     }
 
+    pub fn transfer_policy(&self, deps: Deps) -> StdResult<TransferPolicyResponse> {
+        let policy = self.transfer_policy.may_load(deps.storage)?;
+        Ok(TransferPolicyResponse {
+            policy: policy.unwrap_or(TransferPolicy::Allow {}),
+        })
+    }
+
+    pub fn permit_nonce(&self, deps: Deps, owner: String) -> StdResult<PermitNonceResponse> {
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let nonce = self
+            .permit_nonces
+            .may_load(deps.storage, &owner_addr)?
+            .unwrap_or_default();
+        Ok(PermitNonceResponse { nonce })
+    }
+
+    pub fn num_tokens_of(&self, deps: Deps, owner: String) -> StdResult<NumTokensResponse> {
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let count = self
+            .owner_counts
+            .may_load(deps.storage, &owner_addr)?
+            .unwrap_or_default();
+        Ok(NumTokensResponse { count })
+    }
+
+    pub fn all_owners(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OwnersResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_addr = maybe_addr(deps.api, start_after)?;
+        let start = start_addr.as_ref().map(Bound::exclusive);
+
+        let owners: StdResult<Vec<String>> = self
+            .owner_counts
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(Addr::into_string))
+            .collect();
+        Ok(OwnersResponse { owners: owners? })
+    }
+
     pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg<Q>) -> StdResult<Binary> {
         match msg {
             QueryMsg::Minter {} => to_binary(&self.minter(deps)?),
+            QueryMsg::MetadataFrozen { token_id } => {
+                to_binary(&self.metadata_frozen(deps, token_id)?)
+            }
+            QueryMsg::Lock { token_id } => to_binary(&self.token_lock(deps, env, token_id)?),
+            QueryMsg::TransferPolicy {} => to_binary(&self.transfer_policy(deps)?),
+            QueryMsg::PermitNonce { owner } => to_binary(&self.permit_nonce(deps, owner)?),
+            QueryMsg::Hooks {} => to_binary(&self.hooks.query_hooks(deps)?),
             QueryMsg::ContractInfo {} => to_binary(&self.contract_info(deps)?),
             QueryMsg::NftInfo { token_id } => to_binary(&self.nft_info(deps, token_id)?),
             QueryMsg::OwnerOf {
@@ -235,7 +383,7 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
             QueryMsg::AllNftInfo {
                 token_id,
                 include_expired,
-            } => to_binary(&self.all_nft_info(
+            } => to_binary(&self.all_nft_info_with_lock(
                 deps,
                 env,
                 token_id,
@@ -280,6 +428,15 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
             } => {
                 to_binary(&self.approvals(deps, env, token_id, include_expired.unwrap_or(false))?)
             }
+            QueryMsg::NumTokensOf { owner } => to_binary(&self.num_tokens_of(deps, owner)?),
+            QueryMsg::AllOwners { start_after, limit } => {
+                to_binary(&self.all_owners(deps, start_after, limit)?)
+            }
+            QueryMsg::TokenHistory {
+                token_id,
+                start_after,
+                limit,
+            } => to_binary(&self.token_history(deps, token_id, start_after, limit)?),
             QueryMsg::Extension { msg: _ } => Ok(Binary::default()),
         }
     }
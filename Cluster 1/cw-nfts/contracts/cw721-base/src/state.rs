@@ -1,11 +1,14 @@
-use cosmwasm_std::{Addr, BlockInfo, CustomMsg, StdResult, Storage};
+use cosmwasm_std::{Addr, BlockInfo, CustomMsg, Empty, Order, StdResult, Storage, Timestamp};
 use cw721::{ContractInfoResponse, Cw721, Expiration};
+use cw_controllers::Hooks;
 use cw_storage_plus::{Index, IndexedMap, IndexList, Item, Map, MultiIndex};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde::de::DeserializeOwned;
 use std::marker::PhantomData;
 
+use crate::msg::TransferPolicy;
+
 pub struct Cw721Contract<'a, T, C, E, Q>
     where
         T: Serialize + DeserializeOwned + Clone,
@@ -13,11 +16,39 @@ pub struct Cw721Contract<'a, T, C, E, Q>
         E: CustomMsg,
 {
     pub contract_info: Item<'a, ContractInfoResponse>,
+    /// Removed for good once the minter renounces
     pub minter: Item<'a, Addr>,
+    pub pending_minter: Item<'a, PendingMinter>,
     pub token_count: Item<'a, u64>,
+    /// Tokens minted over the contract's lifetime, burning does not lower it
+    pub minted: Item<'a, u64>,
+    /// Cap on minted, if the collection was instantiated with one
+    pub max_supply: Item<'a, u64>,
+    /// Next id handed out to tokens minted without a token_id
+    pub next_token_id: Item<'a, u64>,
     /// Stored as (granter, operator) giving operator full control over granter's account
     pub operators: Map<'a, (&'a Addr, &'a Addr), Expiration>,
     pub tokens: IndexedMap<'a, &'a str, TokenInfo<T>, TokenIndexes<'a, T>>,
+    /// Number of tokens held by each owner, owners are removed when it drops to 0
+    pub owner_counts: Map<'a, &'a Addr, u64>,
+    /// Set once the minter freezes the metadata of the whole collection
+    pub metadata_frozen: Item<'a, bool>,
+    /// Tokens whose metadata the minter has frozen one by one
+    pub frozen_tokens: Map<'a, &'a str, Empty>,
+    /// Unset means TransferPolicy::Allow
+    pub transfer_policy: Item<'a, TransferPolicy>,
+    /// Contracts notified of every mint, transfer and burn
+    pub hooks: Hooks<'a>,
+    /// The addresses behind hooks, which Hooks keeps private, so move_storage can move them
+    pub(crate) hook_addrs: Item<'a, Vec<Addr>>,
+    /// Account each token's owner allowed to lock it, cleared when the token moves
+    pub lockers: Map<'a, &'a str, Addr>,
+    /// Tokens that can't be transferred, sent or burned until the lock expires or is lifted
+    pub locks: Map<'a, &'a str, TokenLock>,
+    /// Nonce each owner's next permit has to use, so signed permits can't be replayed
+    pub permit_nonces: Map<'a, &'a Addr, u64>,
+    /// Every change of ownership of a token, numbered from 0 per token
+    pub token_history: Map<'a, (&'a str, u64), OwnershipChange>,
 
     pub(crate) _custom_response: PhantomData<C>,
     pub(crate) _custom_query: PhantomData<Q>,
@@ -40,14 +71,58 @@ impl<T, C, E, Q> Default for Cw721Contract<'static, T, C, E, Q>
         Q: CustomMsg,
 {
     fn default() -> Self {
-        Self::new(
-            "nft_info",
-            "minter",
-            "num_tokens",
-            "operators",
-            "tokens",
-            "tokens__owner",
-        )
+        Self::new(Cw721StorageKeys::default())
+    }
+}
+
+/// The storage key of every Item and Map of a Cw721Contract, pass the keys of an older release
+/// to Cw721Contract::new to read its storage, e.g. with move_storage
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cw721StorageKeys<'a> {
+    pub contract: &'a str,
+    pub minter: &'a str,
+    pub pending_minter: &'a str,
+    pub token_count: &'a str,
+    pub minted: &'a str,
+    pub max_supply: &'a str,
+    pub next_token_id: &'a str,
+    pub operators: &'a str,
+    pub tokens: &'a str,
+    pub tokens_owner: &'a str,
+    pub owner_counts: &'a str,
+    pub metadata_frozen: &'a str,
+    pub frozen_tokens: &'a str,
+    pub transfer_policy: &'a str,
+    pub hooks: &'a str,
+    pub lockers: &'a str,
+    pub locks: &'a str,
+    pub permit_nonces: &'a str,
+    pub token_history: &'a str,
+}
+
+impl Default for Cw721StorageKeys<'static> {
+    fn default() -> Self {
+        Cw721StorageKeys {
+            contract: "nft_info",
+            minter: "minter",
+            pending_minter: "pending_minter",
+            token_count: "num_tokens",
+            minted: "minted",
+            max_supply: "max_supply",
+            next_token_id: "next_token_id",
+            operators: "operators",
+            tokens: "tokens",
+            tokens_owner: "tokens__owner",
+            owner_counts: "owner_counts",
+            metadata_frozen: "metadata_frozen",
+            frozen_tokens: "frozen_tokens",
+            transfer_policy: "transfer_policy",
+            hooks: "hooks",
+            lockers: "lockers",
+            locks: "locks",
+            permit_nonces: "permit_nonces",
+            token_history: "token_history",
+        }
     }
 }
 
@@ -57,23 +132,30 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         E: CustomMsg,
         Q: CustomMsg,
 {
-    fn new(
-        contract_key: &'a str,
-        minter_key: &'a str,
-        token_count_key: &'a str,
-        operator_key: &'a str,
-        tokens_key: &'a str,
-        tokens_owner_key: &'a str,
-    ) -> Self {
+    pub fn new(keys: Cw721StorageKeys<'a>) -> Self {
         let indexes = TokenIndexes {
-            owner: MultiIndex::new(token_owner_idx, tokens_key, tokens_owner_key),
+            owner: MultiIndex::new(token_owner_idx, keys.tokens, keys.tokens_owner),
         };
         Self {
-            contract_info: Item::new(contract_key),
-            minter: Item::new(minter_key),
-            token_count: Item::new(token_count_key),
-            operators: Map::new(operator_key),
-            tokens: IndexedMap::new(tokens_key, indexes),
+            contract_info: Item::new(keys.contract),
+            minter: Item::new(keys.minter),
+            pending_minter: Item::new(keys.pending_minter),
+            token_count: Item::new(keys.token_count),
+            minted: Item::new(keys.minted),
+            max_supply: Item::new(keys.max_supply),
+            next_token_id: Item::new(keys.next_token_id),
+            operators: Map::new(keys.operators),
+            tokens: IndexedMap::new(keys.tokens, indexes),
+            owner_counts: Map::new(keys.owner_counts),
+            metadata_frozen: Item::new(keys.metadata_frozen),
+            frozen_tokens: Map::new(keys.frozen_tokens),
+            transfer_policy: Item::new(keys.transfer_policy),
+            hooks: Hooks::new(keys.hooks),
+            hook_addrs: Item::new(keys.hooks),
+            lockers: Map::new(keys.lockers),
+            locks: Map::new(keys.locks),
+            permit_nonces: Map::new(keys.permit_nonces),
+            token_history: Map::new(keys.token_history),
             _custom_response: PhantomData,
             _custom_execute: PhantomData,
             _custom_query: PhantomData,
@@ -90,6 +172,49 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
         Ok(val)
     }
 
+    /// true if the token's metadata can no longer be updated, either on its own
+    /// or because the whole collection is frozen
+    pub fn is_metadata_frozen(&self, storage: &dyn Storage, token_id: &str) -> StdResult<bool> {
+        Ok(self.metadata_frozen.may_load(storage)?.unwrap_or_default()
+            || self.frozen_tokens.has(storage, token_id))
+    }
+
+    /// appends a change of ownership to the token's history and keeps owner_counts
+    /// in step, from is None on mint and to is None on burn
+    pub fn record_ownership(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        token_id: &str,
+        from: Option<Addr>,
+        to: Option<Addr>,
+    ) -> StdResult<()> {
+        let last = self
+            .token_history
+            .prefix(token_id)
+            .keys(storage, None, None, Order::Descending)
+            .next()
+            .transpose()?;
+        if let Some(from) = &from {
+            match self.owner_counts.load(storage, from)? {
+                1 => self.owner_counts.remove(storage, from),
+                count => self.owner_counts.save(storage, from, &(count - 1))?,
+            }
+        }
+        if let Some(to) = &to {
+            let count = self.owner_counts.may_load(storage, to)?.unwrap_or_default();
+            self.owner_counts.save(storage, to, &(count + 1))?;
+        }
+        let change = OwnershipChange {
+            from,
+            to,
+            height: block.height,
+            time: block.time,
+        };
+        self.token_history
+            .save(storage, (token_id, last.map_or(0, |id| id + 1)), &change)
+    }
+
     pub fn decrement_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
         let val = self.token_count(storage)? - 1;
         self.token_count.save(storage, &val)?;
@@ -97,6 +222,36 @@ impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingMinter {
+    /// Becomes the minter once it accepts
+    pub minter: Addr,
+    /// The proposal can no longer be accepted after this (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenLock {
+    /// The approved locker that set the lock, only it can lift the lock early
+    pub locker: Addr,
+    /// The lock lifts by itself after this (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
+impl TokenLock {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnershipChange {
+    pub from: Option<Addr>,
+    pub to: Option<Addr>,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TokenInfo<T> {
     /// The owner of the newly minted NFT
@@ -0,0 +1,101 @@
+use cosmwasm_std::{CustomMsg, Order, StdResult, Storage};
+use cw_storage_plus::{Item, Map, Path, PrimaryKey};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::state::Cw721Contract;
+
+impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
+    where
+        T: Serialize + DeserializeOwned + Clone,
+        C: CustomMsg,
+        E: CustomMsg,
+        Q: CustomMsg,
+{
+    /// Moves everything stored under the keys of previous, usually built with new() and the keys
+    /// of an older release, to the keys of this contract. Data only moves into empty storage, so
+    /// keys both layouts share are left alone. Call it from migrate when default() changes.
+    pub fn move_storage(&self, storage: &mut dyn Storage, previous: &Self) -> StdResult<()> {
+        move_item(storage, &previous.contract_info, &self.contract_info)?;
+        move_item(storage, &previous.minter, &self.minter)?;
+        move_item(storage, &previous.pending_minter, &self.pending_minter)?;
+        move_item(storage, &previous.token_count, &self.token_count)?;
+        move_item(storage, &previous.minted, &self.minted)?;
+        move_item(storage, &previous.max_supply, &self.max_supply)?;
+        move_item(storage, &previous.next_token_id, &self.next_token_id)?;
+        move_item(storage, &previous.metadata_frozen, &self.metadata_frozen)?;
+        move_item(storage, &previous.transfer_policy, &self.transfer_policy)?;
+        move_item(storage, &previous.hook_addrs, &self.hook_addrs)?;
+        move_map(storage, &previous.operators, &self.operators)?;
+        move_map(storage, &previous.owner_counts, &self.owner_counts)?;
+        move_map(storage, &previous.frozen_tokens, &self.frozen_tokens)?;
+        move_map(storage, &previous.lockers, &self.lockers)?;
+        move_map(storage, &previous.locks, &self.locks)?;
+        move_map(storage, &previous.permit_nonces, &self.permit_nonces)?;
+        move_map(storage, &previous.token_history, &self.token_history)?;
+
+        // tokens go one by one, so the owner index is rebuilt under the new keys as well
+        if self.tokens.is_empty(storage) {
+            let tokens = previous
+                .tokens
+                .range(storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            for (token_id, token) in tokens {
+                self.tokens.save(storage, &token_id, &token)?;
+                previous.tokens.remove(storage, &token_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// counts every owner's tokens again from scratch
+    pub(crate) fn rebuild_owner_counts(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        self.owner_counts.clear(storage);
+        let owners = self
+            .tokens
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, token)| token.owner))
+            .collect::<StdResult<Vec<_>>>()?;
+        for owner in owners {
+            let count = self.owner_counts.may_load(storage, &owner)?.unwrap_or_default();
+            self.owner_counts.save(storage, &owner, &(count + 1))?;
+        }
+        Ok(())
+    }
+}
+
+fn move_item<V>(storage: &mut dyn Storage, from: &Item<V>, to: &Item<V>) -> StdResult<()>
+    where
+        V: Serialize + DeserializeOwned,
+{
+    if to.may_load(storage)?.is_none() {
+        if let Some(value) = from.may_load(storage)? {
+            to.save(storage, &value)?;
+            from.remove(storage);
+        }
+    }
+    Ok(())
+}
+
+fn move_map<'k, K, V>(
+    storage: &mut dyn Storage,
+    from: &Map<'k, K, V>,
+    to: &Map<'k, K, V>,
+) -> StdResult<()>
+    where
+        K: PrimaryKey<'k>,
+        V: Serialize + DeserializeOwned,
+{
+    if !to.is_empty(storage) {
+        return Ok(());
+    }
+    let entries = from
+        .range_raw(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    // raw keys are relative to the namespace, whatever the key type
+    for (key, value) in entries {
+        Path::<V>::new(to.namespace(), &[&key]).save(storage, &value)?;
+        Path::<V>::new(from.namespace(), &[&key]).remove(storage);
+    }
+    Ok(())
+}
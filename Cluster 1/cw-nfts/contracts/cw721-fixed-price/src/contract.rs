@@ -62,6 +62,8 @@ pub fn instantiate(
                 name: msg.name.clone(),
                 symbol: msg.symbol,
                 minter: env.contract.address.to_string(),
+                max_supply: Some(msg.max_tokens.into()),
+                transfer_policy: None,
             })?,
             funds: vec![],
             admin: None,
@@ -160,7 +162,7 @@ pub fn execute_receive(
     }
 
     let mint_msg = Cw721ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
-        token_id: config.unused_token_id.to_string(),
+        token_id: Some(config.unused_token_id.to_string()),
         owner: sender,
         token_uri: config.token_uri.clone().into(),
         extension: config.extension.clone(),
@@ -227,6 +229,8 @@ mod tests {
                         name: msg.name.clone(),
                         symbol: msg.symbol.clone(),
                         minter: MOCK_CONTRACT_ADDR.to_string(),
+                        max_supply: Some(1),
+                        transfer_policy: None,
                     })
                         .unwrap(),
                     funds: vec![],
@@ -373,7 +377,7 @@ mod tests {
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         let mint_msg = Cw721ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
-            token_id: String::from("0"),
+            token_id: Some(String::from("0")),
             owner: String::from("minter"),
             token_uri: Some(String::from("https://ipfs.io/ipfs/Q")),
             extension: None,
@@ -1,7 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Empty;
 use cw2::set_contract_version;
-pub use cw721_base::{ContractError, InstantiateMsg, MinterResponse, MintMsg};
+pub use cw721_base::{ContractError, InstantiateMsg, MigrateMsg, MinterResponse, MintMsg};
 
 // Version info for migration
 const CONTRACT_NAME: &str = "crates.io:cw721-metadata-onchain";
@@ -67,6 +67,11 @@ pub mod entry {
         Cw721MetadataContract::default().execute(deps, env, info, msg)
     }
 
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+        Cw721MetadataContract::default().migrate(deps, env, CONTRACT_NAME, CONTRACT_VERSION)
+    }
+
     #[entry_point]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         Cw721MetadataContract::default().query(deps, env, msg)
@@ -92,6 +97,8 @@ mod tests {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
             minter: CREATOR.to_string(),
+            max_supply: None,
+            transfer_policy: None,
         };
         contract
             .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
@@ -99,7 +106,7 @@ mod tests {
 
         let token_id = "Enterprise";
         let mint_msg = MintMsg {
-            token_id: token_id.to_string(),
+            token_id: Some(token_id.to_string()),
             owner: "john".to_string(),
             token_uri: Some("https://starships.example.com/Starship/Enterprise.json".into()),
             extension: Some(Metadata {
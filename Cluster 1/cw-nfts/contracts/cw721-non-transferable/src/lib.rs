@@ -1,14 +1,13 @@
 use cosmwasm_std::Empty;
 pub use cw721_base::{
-    ContractError,
-    Cw721Contract, entry::{execute as _execute, query as _query}, ExecuteMsg, Extension, InstantiateMsg as Cw721BaseInstantiateMsg,
-    MinterResponse, MintMsg,
+    ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg as Cw721BaseInstantiateMsg,
+    MigrateMsg, MinterResponse, MintMsg,
 };
+pub use cw721_base::msg::TransferPolicy;
 
 pub use crate::msg::{InstantiateMsg, QueryMsg};
 
 pub mod msg;
-pub mod query;
 pub mod state;
 
 // version info for migration info
@@ -19,15 +18,32 @@ pub type Cw721NonTransferableContract<'a> = Cw721Contract<'a, Extension, Empty,
 
 #[cfg(not(feature = "library"))]
 pub mod entry {
-    use cosmwasm_std::{
-        Addr, Binary, Deps, DepsMut, entry_point, Env, MessageInfo, Response, StdResult, to_binary,
-    };
+    use cosmwasm_std::{Binary, Deps, DepsMut, entry_point, Env, MessageInfo, Response, StdResult};
 
-    use crate::query::admin;
-    use crate::state::{Config, CONFIG};
+    use crate::state::CONFIG;
 
     use super::*;
 
+    /// Without an admin tokens never move, with one only the admin moves them
+    fn transfer_policy(admin: Option<String>) -> TransferPolicy {
+        match admin {
+            Some(admin) => TransferPolicy::AdminOnly { admin },
+            None => TransferPolicy::Deny {},
+        }
+    }
+
+    /// Burning, approving and locking are the admin's alone, so owners can't get rid of tokens
+    /// or hand them to someone else, and without an admin nobody can
+    fn check_admin(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+        let policy = Cw721NonTransferableContract::default()
+            .transfer_policy
+            .may_load(deps.storage)?;
+        match policy {
+            Some(TransferPolicy::AdminOnly { admin }) if info.sender == admin => Ok(()),
+            _ => Err(ContractError::Unauthorized {}),
+        }
+    }
+
     #[entry_point]
     pub fn instantiate(
         mut deps: DepsMut,
@@ -35,20 +51,12 @@ pub mod entry {
         info: MessageInfo,
         msg: InstantiateMsg,
     ) -> Result<Response, ContractError> {
-        let admin_addr: Option<Addr> = msg
-            .admin
-            .as_deref()
-            .map(|s| deps.api.addr_validate(s))
-            .transpose()?;
-
-        let config = Config { admin: admin_addr };
-
-        CONFIG.save(deps.storage, &config)?;
-
         let cw721_base_instantiate_msg = Cw721BaseInstantiateMsg {
             name: msg.name,
             symbol: msg.symbol,
             minter: msg.minter,
+            max_supply: msg.max_supply,
+            transfer_policy: Some(transfer_policy(msg.admin)),
         };
 
         Cw721NonTransferableContract::default().instantiate(
@@ -71,30 +79,245 @@ pub mod entry {
         env: Env,
         info: MessageInfo,
         msg: ExecuteMsg<Extension, Empty>,
-    ) -> Result<Response, cw721_base::ContractError> {
-        let config = CONFIG.load(deps.storage)?;
-        match config.admin {
-            Some(admin) => {
-                if admin == info.sender {
-                    _execute(deps, env, info, msg)
-                } else {
-                    Err(ContractError::Unauthorized {})
-                }
+    ) -> Result<Response, ContractError> {
+        match msg {
+            // the policy is what makes the tokens non-transferable, so it is fixed
+            ExecuteMsg::UpdateTransferPolicy { .. } => Err(ContractError::Unauthorized {}),
+            ExecuteMsg::Approve { .. }
+            | ExecuteMsg::ApproveAll { .. }
+            | ExecuteMsg::Permit { .. }
+            | ExecuteMsg::PermitAll { .. }
+            | ExecuteMsg::ApproveLocker { .. }
+            | ExecuteMsg::Lock { .. }
+            | ExecuteMsg::Burn { .. }
+            | ExecuteMsg::BatchBurn { .. } => {
+                check_admin(deps.as_ref(), &info)?;
+                Cw721NonTransferableContract::default().execute(deps, env, info, msg)
             }
-            None => match msg {
-                ExecuteMsg::Mint(msg) => {
-                    Cw721NonTransferableContract::default().mint(deps, env, info, msg)
-                }
-                _ => Err(ContractError::Unauthorized {}),
-            },
+            _ => Cw721NonTransferableContract::default().execute(deps, env, info, msg),
+        }
+    }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+        let contract = Cw721NonTransferableContract::default();
+        // earlier releases kept the admin in their own config instead of a transfer policy
+        if let Some(config) = CONFIG.may_load(deps.storage)? {
+            let admin = config.admin.map(|admin| admin.into_string());
+            contract.transfer_policy.save(deps.storage, &transfer_policy(admin))?;
+            CONFIG.remove(deps.storage);
         }
+        contract.migrate(deps, env, CONTRACT_NAME, CONTRACT_VERSION)
     }
 
     #[entry_point]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-        match msg {
-            QueryMsg::Admin {} => to_binary(&admin(deps)?),
-            _ => _query(deps, env, msg.into()),
+        Cw721NonTransferableContract::default().query(deps, env, msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{Addr, Deps, DepsMut, from_binary, Response};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cw721::Expiration;
+    use cw721_base::msg::TransferPolicyResponse;
+
+    use crate::entry;
+    use crate::state::{Config, CONFIG};
+
+    use super::*;
+
+    const CREATOR: &str = "creator";
+    const ADMIN: &str = "admin";
+
+    fn setup_contract(deps: DepsMut, admin: Option<&str>) {
+        let msg = InstantiateMsg {
+            admin: admin.map(String::from),
+            name: "Badges".to_string(),
+            symbol: "BDG".to_string(),
+            minter: CREATOR.to_string(),
+            max_supply: None,
+        };
+        entry::instantiate(deps, mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+    }
+
+    fn mint(deps: DepsMut, token_id: &str, owner: &str) {
+        let msg = ExecuteMsg::Mint(MintMsg {
+            token_id: Some(token_id.to_string()),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        });
+        entry::execute(deps, mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+    }
+
+    fn transfer(deps: DepsMut, sender: &str, token_id: &str) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "venus".to_string(),
+            token_id: token_id.to_string(),
+        };
+        entry::execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn policy(deps: Deps) -> TransferPolicy {
+        let res = entry::query(deps, mock_env(), QueryMsg::TransferPolicy {}).unwrap();
+        from_binary::<TransferPolicyResponse>(&res).unwrap().policy
+    }
+
+    #[test]
+    fn transfers_denied_without_admin() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut(), None);
+        mint(deps.as_mut(), "badge", "mars");
+
+        assert_eq!(policy(deps.as_ref()), TransferPolicy::Deny {});
+        let err = transfer(deps.as_mut(), "mars", "badge").unwrap_err();
+        assert_eq!(err, ContractError::TransferDenied {});
+    }
+
+    #[test]
+    fn only_admin_transfers() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut(), Some(ADMIN));
+        mint(deps.as_mut(), "badge", "mars");
+        mint(deps.as_mut(), "medal", ADMIN);
+
+        // the owner can't move its own token
+        let err = transfer(deps.as_mut(), "mars", "badge").unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // the admin moves the tokens it holds
+        transfer(deps.as_mut(), ADMIN, "medal").unwrap();
+    }
+
+    #[test]
+    fn only_admin_burns_approves_and_locks() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut(), Some(ADMIN));
+        mint(deps.as_mut(), "badge", "mars");
+        mint(deps.as_mut(), "medal", ADMIN);
+
+        let owner_msgs = |token_id: &str| {
+            vec![
+                ExecuteMsg::Approve {
+                    spender: "venus".to_string(),
+                    token_id: token_id.to_string(),
+                    expires: None,
+                },
+                ExecuteMsg::ApproveLocker {
+                    locker: "venus".to_string(),
+                    token_id: token_id.to_string(),
+                },
+                ExecuteMsg::Lock {
+                    token_id: token_id.to_string(),
+                    expires: Expiration::Never {},
+                },
+                ExecuteMsg::BatchBurn {
+                    token_ids: vec![token_id.to_string()],
+                },
+                ExecuteMsg::Burn {
+                    token_id: token_id.to_string(),
+                },
+            ]
+        };
+
+        // the owner can't hand over, lock or burn its own token
+        let approve_all = ExecuteMsg::ApproveAll {
+            operator: ADMIN.to_string(),
+            expires: None,
+        };
+        for msg in [vec![approve_all], owner_msgs("badge")].concat() {
+            let err = entry::execute(deps.as_mut(), mock_env(), mock_info("mars", &[]), msg)
+                .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+        }
+
+        // the admin can, for the tokens it holds
+        let msg = ExecuteMsg::Approve {
+            spender: "venus".to_string(),
+            token_id: "medal".to_string(),
+            expires: None,
+        };
+        entry::execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+        let msg = ExecuteMsg::Burn {
+            token_id: "medal".to_string(),
+        };
+        entry::execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn nobody_burns_without_admin() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut(), None);
+        mint(deps.as_mut(), "badge", "mars");
+
+        for sender in ["mars", CREATOR] {
+            let msg = ExecuteMsg::Burn {
+                token_id: "badge".to_string(),
+            };
+            let err = entry::execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+                .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+        }
+    }
+
+    #[test]
+    fn transfer_policy_is_fixed() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut(), Some(ADMIN));
+
+        for sender in [CREATOR, ADMIN] {
+            let msg = ExecuteMsg::UpdateTransferPolicy {
+                policy: TransferPolicy::Allow {},
+            };
+            let err = entry::execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+                .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+        }
+        assert_eq!(
+            policy(deps.as_ref()),
+            TransferPolicy::AdminOnly {
+                admin: ADMIN.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn migrating_config() {
+        for (admin, expected) in [
+            (
+                Some(ADMIN),
+                TransferPolicy::AdminOnly {
+                    admin: ADMIN.to_string(),
+                },
+            ),
+            (None, TransferPolicy::Deny {}),
+        ] {
+            // an older release kept the admin in CONFIG and had no transfer policy
+            let mut deps = mock_dependencies();
+            let msg = Cw721BaseInstantiateMsg {
+                name: "Badges".to_string(),
+                symbol: "BDG".to_string(),
+                minter: CREATOR.to_string(),
+                max_supply: None,
+                transfer_policy: None,
+            };
+            Cw721NonTransferableContract::default()
+                .instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg)
+                .unwrap();
+            cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.15.0").unwrap();
+            let config = Config {
+                admin: admin.map(Addr::unchecked),
+            };
+            CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+            entry::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+            assert_eq!(policy(deps.as_ref()), expected);
+            assert_eq!(CONFIG.may_load(deps.as_ref().storage).unwrap(), None);
+            let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+            assert_eq!(version.contract, CONTRACT_NAME);
+            assert_eq!(version.version, CONTRACT_VERSION);
         }
     }
 }
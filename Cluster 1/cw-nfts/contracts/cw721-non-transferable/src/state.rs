@@ -2,6 +2,7 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
 use cw_storage_plus::Item;
 
+/// Written by releases before the admin became a transfer policy, only read when migrating
 #[cw_serde]
 pub struct Config {
     pub admin: Option<Addr>,
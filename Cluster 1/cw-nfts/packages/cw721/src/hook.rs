@@ -0,0 +1,66 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, CosmosMsg, StdResult, to_binary, WasmMsg};
+use schemars::JsonSchema;
+
+/// OwnerDiff shows the owner of a token before and after a change,
+/// old is None on mint and new is None on burn
+#[cw_serde]
+pub struct OwnerDiff {
+    pub token_id: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl OwnerDiff {
+    pub fn new<T: Into<String>>(token_id: T, old: Option<String>, new: Option<String>) -> Self {
+        OwnerDiff {
+            token_id: token_id.into(),
+            old,
+            new,
+        }
+    }
+}
+
+/// Cw721HookMsg should be de/serialized under `Cw721Hook()` variant in a ExecuteMsg.
+/// It is sent to every registered hook whenever tokens are minted, moved or burned
+#[cw_serde]
+pub struct Cw721HookMsg {
+    pub diffs: Vec<OwnerDiff>,
+}
+
+impl Cw721HookMsg {
+    pub fn one(diff: OwnerDiff) -> Self {
+        Cw721HookMsg { diffs: vec![diff] }
+    }
+
+    pub fn new(diffs: Vec<OwnerDiff>) -> Self {
+        Cw721HookMsg { diffs }
+    }
+
+    /// serializes the message
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = HookExecuteMsg::Cw721Hook(self);
+        to_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<T: Into<String>, C>(self, contract_addr: T) -> StdResult<CosmosMsg<C>>
+        where
+            C: Clone + std::fmt::Debug + PartialEq + JsonSchema,
+    {
+        let msg = self.into_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// This is just a helper to properly serialize the above message.
+/// The actual hook should include this variant in the larger ExecuteMsg enum
+#[cw_serde]
+enum HookExecuteMsg {
+    Cw721Hook(Cw721HookMsg),
+}
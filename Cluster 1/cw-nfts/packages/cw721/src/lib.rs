@@ -1,5 +1,6 @@
 pub use cw_utils::Expiration;
 
+pub use crate::hook::{Cw721HookMsg, OwnerDiff};
 pub use crate::msg::Cw721ExecuteMsg;
 pub use crate::query::{
     AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
@@ -9,6 +10,7 @@ pub use crate::query::{
 pub use crate::receiver::Cw721ReceiveMsg;
 pub use crate::traits::{Cw721, Cw721Execute, Cw721Query};
 
+mod hook;
 mod msg;
 mod query;
 mod receiver;
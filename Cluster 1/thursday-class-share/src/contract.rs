@@ -1,32 +1,21 @@
-// allow unimplemented functions
-#![allow(unused_variables, dead_code)]
-// allow unused imports
-#![allow(unused_imports)]
-
-/*
-To Do:
-
-Execute Messages: 
-
-Increment -- Increment by 1
-Decrement -- Decrement by 1
-IncrementBy -- Increment by a given input amount
-DecrementBy -- Decrement by a given input amount
-ReflectFunds -- Send funds to the contract and then return that amount back to the sender
-
-Create tests for new execute messages
-
-*/
-
-
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Storage, Uint128,
+};
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GetCountResponse, InstantiateMsg, QueryMsg};
-use crate::state::{State, STATE};
+use crate::msg::{
+    ExecuteMsg, GetCountResponse, HistoryResponse, InstantiateMsg, OperatorsResponse,
+    OwnerResponse, QueryMsg,
+};
+use crate::state::{
+    HistoryEntry, Permission, Policy, State, HISTORY, HISTORY_SEQ, HISTORY_SIZE, OPERATORS,
+    PENDING_OWNER, POLICY, STATE, USER_COUNTS,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:thursday-class-share";
@@ -42,9 +31,14 @@ pub fn instantiate(
     let state = State {
         count: msg.count,
         owner: info.sender.clone(),
+        min: msg.min,
+        max: msg.max,
+        reset: false,
     };
+    execute::validate_bounds(&state)?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
+    POLICY.save(deps.storage, &Policy::default())?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -55,93 +49,331 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    execute::authorize(deps.as_ref(), &info.sender, &msg)?;
     match msg {
-        ExecuteMsg::Increment {} => execute::increment(deps),
-        ExecuteMsg::Reset { count } => execute::reset(deps, info, count),
-        ExecuteMsg::Decrement {} => execute::decrement(deps),
-        ExecuteMsg::IncrementBy { count } => execute::increment_by(deps, count),
-        ExecuteMsg::DecrementBy { count } => execute::decrement_by(deps, count),
-        ExecuteMsg::ReflectFunds { amount } => { Ok(Response::new() )}//execute::reflect_funds(deps, info, amount),
+        ExecuteMsg::Increment {} => execute::increment(deps, env, info),
+        ExecuteMsg::Reset { count } => execute::reset(deps, env, info, count),
+        ExecuteMsg::Decrement {} => execute::decrement(deps, env, info),
+        ExecuteMsg::IncrementBy { count } => execute::increment_by(deps, env, info, count),
+        ExecuteMsg::DecrementBy { count } => execute::decrement_by(deps, env, info, count),
+        ExecuteMsg::ReflectFunds { amount } => execute::reflect_funds(info, amount),
+        ExecuteMsg::UpdateBounds { min, max } => execute::update_bounds(deps, info, min, max),
+        ExecuteMsg::AddOperator { address } => execute::add_operator(deps, info, address),
+        ExecuteMsg::RemoveOperator { address } => execute::remove_operator(deps, info, address),
+        ExecuteMsg::UpdatePolicy { policy } => execute::update_policy(deps, info, policy),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            execute::transfer_ownership(deps, info, new_owner)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute::accept_ownership(deps, info),
     }
 }
 
 pub mod execute {
     use super::*;
 
-    pub fn increment(deps: DepsMut) -> Result<Response, ContractError> {
+    // bounded returns the new count, failing if the arithmetic overflowed (None) or the value is out of bounds
+    fn bounded(state: &State, count: Option<i32>) -> Result<i32, ContractError> {
+        let count = count.ok_or(ContractError::Overflow {})?;
+        if !state.in_bounds(count) {
+            return Err(ContractError::OutOfBounds { count });
+        }
+        Ok(count)
+    }
+
+    // authorize checks the sender against the permission the policy requires for msg,
+    // messages outside the policy check the sender themselves
+    pub fn authorize(deps: Deps, sender: &Addr, msg: &ExecuteMsg) -> Result<(), ContractError> {
+        let policy = POLICY.load(deps.storage)?;
+        let permission = match msg {
+            ExecuteMsg::Increment {} => policy.increment,
+            ExecuteMsg::Decrement {} => policy.decrement,
+            ExecuteMsg::IncrementBy { .. } => policy.increment_by,
+            ExecuteMsg::DecrementBy { .. } => policy.decrement_by,
+            _ => return Ok(()),
+        };
+        if permission == Permission::Public {
+            return Ok(());
+        }
+        if permission == Permission::Operators && OPERATORS.has(deps.storage, sender) {
+            return Ok(());
+        }
+        only_owner(deps, sender)
+    }
+
+    fn only_owner(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+        let state = STATE.load(deps.storage)?;
+        if *sender != state.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(())
+    }
+
+    // add returns None when count + delta does not fit in an i32
+    fn add(count: i32, delta: i64) -> Option<i32> {
+        i32::try_from(i64::from(count) + delta).ok()
+    }
+
+    // change moves both the global count and the sender's own count by delta
+    fn change(deps: DepsMut, env: &Env, sender: &Addr, delta: i64) -> Result<(), ContractError> {
         STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            state.count += 1;
+            state.count = bounded(&state, add(state.count, delta))?;
             Ok(state)
         })?;
+        USER_COUNTS.update(deps.storage, sender, |count| {
+            add(count.unwrap_or_default(), delta).ok_or(ContractError::Overflow {})
+        })?;
+        record(deps.storage, env, sender, delta)
+    }
+
+    // record appends a change to the history, dropping the oldest entry once HISTORY_SIZE are kept
+    fn record(
+        storage: &mut dyn Storage,
+        env: &Env,
+        sender: &Addr,
+        delta: i64,
+    ) -> Result<(), ContractError> {
+        let id = HISTORY_SEQ.may_load(storage)?.unwrap_or_default();
+        HISTORY_SEQ.save(storage, &(id + 1))?;
+        let entry = HistoryEntry {
+            id,
+            sender: sender.clone(),
+            delta,
+            height: env.block.height,
+        };
+        HISTORY.save(storage, id, &entry)?;
+        if id >= HISTORY_SIZE {
+            HISTORY.remove(storage, id - HISTORY_SIZE);
+        }
+        Ok(())
+    }
+
+    // validate_bounds checks the bounds are ordered and contain the current count
+    pub fn validate_bounds(state: &State) -> Result<(), ContractError> {
+        if let (Some(min), Some(max)) = (state.min, state.max) {
+            if min > max {
+                return Err(ContractError::InvalidBounds {});
+            }
+        }
+        if !state.in_bounds(state.count) {
+            return Err(ContractError::OutOfBounds { count: state.count });
+        }
+        Ok(())
+    }
+
+    pub fn increment(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        change(deps, &env, &info.sender, 1)?;
 
         Ok(Response::new().add_attribute("action", "increment"))
     }
 
-    pub fn reset(deps: DepsMut, info: MessageInfo, count: i32) -> Result<Response, ContractError> {
+    pub fn reset(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        count: i32,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        let delta = i64::from(count) - i64::from(state.count);
+        state.count = bounded(&state, Some(count))?;
+        state.reset = true;
+        STATE.save(deps.storage, &state)?;
+        record(deps.storage, &env, &info.sender, delta)?;
+        Ok(Response::new().add_attribute("action", "reset"))
+    }
+
+    pub fn decrement(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        change(deps, &env, &info.sender, -1)?;
+
+        Ok(Response::new().add_attribute("action", "decrement"))
+    }
+
+    pub fn increment_by(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        count: i32,
+    ) -> Result<Response, ContractError> {
+        change(deps, &env, &info.sender, i64::from(count))?;
+
+        Ok(Response::new().add_attribute("action", "increment_by"))
+    }
+
+    pub fn decrement_by(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        count: i32,
+    ) -> Result<Response, ContractError> {
+        change(deps, &env, &info.sender, -i64::from(count))?;
+
+        Ok(Response::new().add_attribute("action", "decrement_by"))
+    }
+
+    pub fn update_bounds(
+        deps: DepsMut,
+        info: MessageInfo,
+        min: Option<i32>,
+        max: Option<i32>,
+    ) -> Result<Response, ContractError> {
         STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
             if info.sender != state.owner {
                 return Err(ContractError::Unauthorized {});
             }
-            state.count = count;
+            state.min = min;
+            state.max = max;
+            validate_bounds(&state)?;
             Ok(state)
         })?;
-        Ok(Response::new().add_attribute("action", "reset"))
+        Ok(Response::new().add_attribute("action", "update_bounds"))
     }
 
-    pub fn decrement(deps: DepsMut) -> Result<Response, ContractError> {
-        STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            state.count -= 1;
-            Ok(state)
-        })?;
+    pub fn add_operator(
+        deps: DepsMut,
+        info: MessageInfo,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        only_owner(deps.as_ref(), &info.sender)?;
+        let operator = deps.api.addr_validate(&address)?;
+        OPERATORS.save(deps.storage, &operator, &Empty {})?;
+        Ok(Response::new()
+            .add_attribute("action", "add_operator")
+            .add_attribute("operator", operator))
+    }
 
-        Ok(Response::new().add_attribute("action", "decrement"))
+    pub fn remove_operator(
+        deps: DepsMut,
+        info: MessageInfo,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        only_owner(deps.as_ref(), &info.sender)?;
+        let operator = deps.api.addr_validate(&address)?;
+        OPERATORS.remove(deps.storage, &operator);
+        Ok(Response::new()
+            .add_attribute("action", "remove_operator")
+            .add_attribute("operator", operator))
     }
 
-    pub fn increment_by(deps: DepsMut, count: i32) -> Result<Response, ContractError> {
-        STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            state.count += count;
-            Ok(state)
-        })?;
+    pub fn update_policy(
+        deps: DepsMut,
+        info: MessageInfo,
+        policy: Policy,
+    ) -> Result<Response, ContractError> {
+        only_owner(deps.as_ref(), &info.sender)?;
+        POLICY.save(deps.storage, &policy)?;
+        Ok(Response::new().add_attribute("action", "update_policy"))
+    }
 
-        Ok(Response::new().add_attribute("action", "increment_by"))
+    // transfer_ownership only proposes the new owner, who has to accept before anything changes;
+    // proposing another address replaces the pending one
+    pub fn transfer_ownership(
+        deps: DepsMut,
+        info: MessageInfo,
+        new_owner: String,
+    ) -> Result<Response, ContractError> {
+        only_owner(deps.as_ref(), &info.sender)?;
+        let new_owner = deps.api.addr_validate(&new_owner)?;
+        PENDING_OWNER.save(deps.storage, &new_owner)?;
+        Ok(Response::new()
+            .add_attribute("action", "transfer_ownership")
+            .add_attribute("pending_owner", new_owner))
     }
 
-    pub fn decrement_by(deps: DepsMut, count: i32) -> Result<Response, ContractError> {
-        STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            state.count -= count;
+    pub fn accept_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        let pending = PENDING_OWNER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingOwner {})?;
+        if info.sender != pending {
+            return Err(ContractError::Unauthorized {});
+        }
+        STATE.update(deps.storage, |mut state| -> StdResult<_> {
+            state.owner = pending;
             Ok(state)
         })?;
+        PENDING_OWNER.remove(deps.storage);
+        Ok(Response::new()
+            .add_attribute("action", "accept_ownership")
+            .add_attribute("owner", info.sender))
+    }
 
-        Ok(Response::new().add_attribute("action", "decrement_by"))
-    }}
-
-    reflect_funds function takes the amount of coins sent to the contract and returns them to the sender
-    pub fn reflect_funds(deps: DepsMut, info: MessageInfo, amount: u128) -> Result<Response, ContractError> {
-        let mut response = Response::new();
-        response.add_attribute("action", "reflect_funds");
-        response.add_attribute("amount", amount.to_string());
-        response.add_attribute("sender", info.sender);
-        response.add_message(CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: vec![Coin {
-                denom: "ujuno".to_string(),
-                amount,
-            }],
-        }));
-        Ok(response)
+    // total adds up the coins of one denom, a denom may appear more than once in a list
+    fn total(coins: &[Coin], denom: &str) -> StdResult<Uint128> {
+        coins
+            .iter()
+            .filter(|coin| coin.denom == denom)
+            .try_fold(Uint128::zero(), |total, coin| total.checked_add(coin.amount))
+            .map_err(StdError::from)
+    }
+
+    // reflect_funds sends every coin sent with the message straight back to the sender,
+    // amount lists what the caller expects back and has to match what was sent denom by denom
+    pub fn reflect_funds(info: MessageInfo, amount: Vec<Coin>) -> Result<Response, ContractError> {
+        if info.funds.is_empty() {
+            return Err(ContractError::NoFunds {});
+        }
+        for denom in amount.iter().chain(&info.funds).map(|coin| &coin.denom) {
+            let expected = total(&amount, denom)?;
+            let sent = total(&info.funds, denom)?;
+            if expected != sent {
+                return Err(ContractError::FundsMismatch {
+                    denom: denom.clone(),
+                    expected: expected.u128(),
+                    sent: sent.u128(),
+                });
+            }
+        }
+
+        let funds = info
+            .funds
+            .iter()
+            .map(|coin| coin.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: info.funds,
+            })
+            .add_attribute("action", "reflect_funds")
+            .add_attribute("sender", info.sender)
+            .add_attribute("funds", funds))
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetCount {} => to_binary(&query::count(deps)?),
+        QueryMsg::GetUserCount { address } => to_binary(&query::user_count(deps, address)?),
+        QueryMsg::History { start_after, limit } => {
+            to_binary(&query::history(deps, start_after, limit)?)
+        }
+        QueryMsg::ResetOrNot {} => to_binary(&query::reset_or_not(deps)?),
+        QueryMsg::Owner {} => to_binary(&query::owner(deps)?),
+        QueryMsg::Operators { start_after, limit } => {
+            to_binary(&query::operators(deps, start_after, limit)?)
+        }
+        QueryMsg::Policy {} => to_binary(&POLICY.load(deps.storage)?),
     }
 }
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 pub mod query {
     use super::*;
 
@@ -149,19 +381,76 @@ pub mod query {
         let state = STATE.load(deps.storage)?;
         Ok(GetCountResponse { count: state.count })
     }
+
+    pub fn user_count(deps: Deps, address: String) -> StdResult<GetCountResponse> {
+        let address = deps.api.addr_validate(&address)?;
+        let count = USER_COUNTS
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default();
+        Ok(GetCountResponse { count })
+    }
+
+    pub fn history(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<HistoryResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+        let entries = HISTORY
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, entry)| entry))
+            .collect::<StdResult<_>>()?;
+        Ok(HistoryResponse { entries })
+    }
+
+    pub fn reset_or_not(deps: Deps) -> StdResult<bool> {
+        let state = STATE.load(deps.storage)?;
+        Ok(state.reset)
+    }
+
+    pub fn owner(deps: Deps) -> StdResult<OwnerResponse> {
+        let state = STATE.load(deps.storage)?;
+        Ok(OwnerResponse {
+            owner: state.owner,
+            pending_owner: PENDING_OWNER.may_load(deps.storage)?,
+        })
+    }
+
+    pub fn operators(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OperatorsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|address| deps.api.addr_validate(&address))
+            .transpose()?;
+        let start = start_after.as_ref().map(Bound::exclusive);
+        let operators = OPERATORS
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+        Ok(OperatorsResponse { operators })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    use cosmwasm_std::{coin, coins, from_binary, CosmosMsg};
 
     #[test]
     fn proper_initialization() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            min: None,
+            max: None,
+        };
         let info = mock_info("creator", &coins(1000, "earth"));
 
         // we can just call .unwrap() to assert this was a success
@@ -178,7 +467,11 @@ mod tests {
     fn increment() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            min: None,
+            max: None,
+        };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -197,7 +490,11 @@ mod tests {
     fn reset() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            min: None,
+            max: None,
+        };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -220,4 +517,532 @@ mod tests {
         let value: GetCountResponse = from_binary(&res).unwrap();
         assert_eq!(5, value.count);
     }
+
+    #[test]
+    fn reflect_funds() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            min: None,
+            max: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // every denom sent is sent back to the sender
+        let funds = vec![coin(2, "token"), coin(3, "earth")];
+        let info = mock_info("anyone", &funds);
+        let msg = ExecuteMsg::ReflectFunds {
+            amount: funds.clone(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "anyone".to_string(),
+                amount: funds,
+            })
+        );
+
+        // nothing sent
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::ReflectFunds { amount: vec![] };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::NoFunds {}) => {}
+            _ => panic!("Must return no funds error"),
+        }
+
+        // amount does not match what was sent
+        let info = mock_info("anyone", &coins(2, "token"));
+        let msg = ExecuteMsg::ReflectFunds {
+            amount: coins(5, "token"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::FundsMismatch {
+                denom,
+                expected: 5,
+                sent: 2,
+            }) if denom == "token" => {}
+            _ => panic!("Must return funds mismatch error"),
+        }
+
+        // the same total in another denom is not a match
+        let info = mock_info("anyone", &coins(5, "earth"));
+        let msg = ExecuteMsg::ReflectFunds {
+            amount: coins(5, "token"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::FundsMismatch {
+                denom,
+                expected: 5,
+                sent: 0,
+            }) if denom == "token" => {}
+            _ => panic!("Must return funds mismatch error"),
+        }
+
+        // a denom sent but not expected is not a match either
+        let info = mock_info("anyone", &[coin(5, "token"), coin(1, "earth")]);
+        let msg = ExecuteMsg::ReflectFunds {
+            amount: coins(5, "token"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::FundsMismatch {
+                denom,
+                expected: 0,
+                sent: 1,
+            }) if denom == "earth" => {}
+            _ => panic!("Must return funds mismatch error"),
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: i32::MAX - 1,
+            min: None,
+            max: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // reaching i32::MAX is fine, going past it is not
+        let msg = ExecuteMsg::Increment {};
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        match res {
+            Err(ContractError::Overflow {}) => {}
+            _ => panic!("Must return overflow error"),
+        }
+
+        // decrementing by a negative amount overflows the same way
+        let msg = ExecuteMsg::DecrementBy { count: -1 };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        match res {
+            Err(ContractError::Overflow {}) => {}
+            _ => panic!("Must return overflow error"),
+        }
+
+        // as does going below i32::MIN
+        let msg = ExecuteMsg::Reset { count: i32::MIN };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Decrement {};
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::Overflow {}) => {}
+            _ => panic!("Must return overflow error"),
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        let mut deps = mock_dependencies();
+
+        // the initial count must be within the bounds
+        let msg = InstantiateMsg {
+            count: 17,
+            min: Some(0),
+            max: Some(10),
+        };
+        let info = mock_info("creator", &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
+        match res {
+            Err(ContractError::OutOfBounds { count: 17 }) => {}
+            _ => panic!("Must return out of bounds error"),
+        }
+
+        let msg = InstantiateMsg {
+            count: 8,
+            min: Some(0),
+            max: Some(10),
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // every mutating message stops at the bounds
+        let msg = ExecuteMsg::IncrementBy { count: 2 };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let moves = vec![
+            ExecuteMsg::Increment {},
+            ExecuteMsg::IncrementBy { count: 1 },
+            ExecuteMsg::DecrementBy { count: 11 },
+            ExecuteMsg::Reset { count: -1 },
+        ];
+        for msg in moves {
+            let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+            match res {
+                Err(ContractError::OutOfBounds { .. }) => {}
+                _ => panic!("Must return out of bounds error"),
+            }
+        }
+        let msg = ExecuteMsg::Reset { count: 0 };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Decrement {};
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        match res {
+            Err(ContractError::OutOfBounds { count: -1 }) => {}
+            _ => panic!("Must return out of bounds error"),
+        }
+
+        // only the owner can update the bounds, which must be ordered and contain the count
+        let msg = ExecuteMsg::UpdateBounds {
+            min: Some(-5),
+            max: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let msg = ExecuteMsg::UpdateBounds {
+            min: Some(5),
+            max: Some(-5),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        match res {
+            Err(ContractError::InvalidBounds {}) => {}
+            _ => panic!("Must return invalid bounds error"),
+        }
+        let msg = ExecuteMsg::UpdateBounds {
+            min: Some(5),
+            max: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        match res {
+            Err(ContractError::OutOfBounds { count: 0 }) => {}
+            _ => panic!("Must return out of bounds error"),
+        }
+        let msg = ExecuteMsg::UpdateBounds {
+            min: Some(-5),
+            max: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // the new bounds apply straight away
+        let msg = ExecuteMsg::DecrementBy { count: 5 };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::IncrementBy { count: 1000 };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetCount {}).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(995, value.count);
+    }
+
+    #[test]
+    fn user_counts_and_history() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 0,
+            min: None,
+            max: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // each sender keeps their own count next to the global one
+        let mut env = mock_env();
+        let changes = vec![
+            ("alice", ExecuteMsg::IncrementBy { count: 5 }),
+            ("bob", ExecuteMsg::Increment {}),
+            ("alice", ExecuteMsg::DecrementBy { count: 2 }),
+            ("bob", ExecuteMsg::Decrement {}),
+        ];
+        for (sender, msg) in changes {
+            env.block.height += 1;
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg).unwrap();
+        }
+        let msg = QueryMsg::GetUserCount {
+            address: "alice".to_string(),
+        };
+        let value: GetCountResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(3, value.count);
+        let msg = QueryMsg::GetUserCount {
+            address: "bob".to_string(),
+        };
+        let value: GetCountResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(0, value.count);
+        let msg = QueryMsg::GetUserCount {
+            address: "carol".to_string(),
+        };
+        let value: GetCountResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(0, value.count);
+
+        // a failed change is not recorded
+        let msg = ExecuteMsg::Reset { count: 1 };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+
+        // the history records who changed the count, by how much and when
+        let msg = QueryMsg::History {
+            start_after: None,
+            limit: Some(3),
+        };
+        let res: HistoryResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let height = mock_env().block.height;
+        assert_eq!(
+            res.entries,
+            vec![
+                HistoryEntry {
+                    id: 0,
+                    sender: Addr::unchecked("alice"),
+                    delta: 5,
+                    height: height + 1,
+                },
+                HistoryEntry {
+                    id: 1,
+                    sender: Addr::unchecked("bob"),
+                    delta: 1,
+                    height: height + 2,
+                },
+                HistoryEntry {
+                    id: 2,
+                    sender: Addr::unchecked("alice"),
+                    delta: -2,
+                    height: height + 3,
+                },
+            ]
+        );
+        let msg = QueryMsg::History {
+            start_after: Some(2),
+            limit: None,
+        };
+        let res: HistoryResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let ids: Vec<_> = res.entries.iter().map(|e| (e.id, e.delta)).collect();
+        assert_eq!(ids, vec![(3, -1)]);
+
+        // only the last HISTORY_SIZE changes are kept
+        for _ in 0..HISTORY_SIZE {
+            let msg = ExecuteMsg::Increment {};
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        }
+        let msg = QueryMsg::History {
+            start_after: None,
+            limit: Some(1),
+        };
+        let res: HistoryResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.entries[0].id, 4);
+    }
+
+    #[test]
+    fn reset_or_not() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            min: None,
+            max: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ResetOrNot {}).unwrap();
+        assert!(!from_binary::<bool>(&res).unwrap());
+
+        // a reset is logged as the difference to the previous count
+        let msg = ExecuteMsg::Reset { count: -3 };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ResetOrNot {}).unwrap();
+        assert!(from_binary::<bool>(&res).unwrap());
+        let msg = QueryMsg::History {
+            start_after: None,
+            limit: None,
+        };
+        let res: HistoryResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.entries[0].delta, -20);
+        assert_eq!(res.entries[0].sender, Addr::unchecked("creator"));
+    }
+
+    #[test]
+    fn operators_and_policy() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            min: None,
+            max: None,
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // everything is public until the owner restricts it
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Policy {}).unwrap();
+        assert_eq!(from_binary::<Policy>(&res).unwrap(), Policy::default());
+        let msg = ExecuteMsg::DecrementBy { count: 5 };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+
+        // only the owner manages operators and the policy
+        let msg = ExecuteMsg::AddOperator {
+            address: "operator".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let policy = Policy {
+            decrement_by: Permission::Operators,
+            increment_by: Permission::Owner,
+            ..Policy::default()
+        };
+        let msg = ExecuteMsg::UpdatePolicy {
+            policy: policy.clone(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("operator", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // operator messages are open to operators and the owner
+        let msg = ExecuteMsg::DecrementBy { count: 5 };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("operator", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // owner messages are not open to operators
+        let msg = ExecuteMsg::IncrementBy { count: 10 };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("operator", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // Increment stays public
+        let msg = ExecuteMsg::Increment {};
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetCount {}).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(13, value.count);
+
+        let msg = QueryMsg::Operators {
+            start_after: None,
+            limit: None,
+        };
+        let res: OperatorsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.operators, vec![Addr::unchecked("operator")]);
+
+        // removed operators lose access straight away
+        let msg = ExecuteMsg::RemoveOperator {
+            address: "operator".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+        let msg = ExecuteMsg::DecrementBy { count: 1 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+    }
+
+    #[test]
+    fn transfer_ownership() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            min: None,
+            max: None,
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // nothing to accept yet
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let res = execute(deps.as_mut(), mock_env(), mock_info("new", &[]), msg);
+        match res {
+            Err(ContractError::NoPendingOwner {}) => {}
+            _ => panic!("Must return no pending owner error"),
+        }
+
+        let msg = ExecuteMsg::TransferOwnership {
+            new_owner: "new".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("new", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // the old owner stays in charge until the new owner accepts
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
+        let value: OwnerResponse = from_binary(&res).unwrap();
+        assert_eq!(value.owner, Addr::unchecked("creator"));
+        assert_eq!(value.pending_owner, Some(Addr::unchecked("new")));
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("new", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
+        let value: OwnerResponse = from_binary(&res).unwrap();
+        assert_eq!(value.owner, Addr::unchecked("new"));
+        assert_eq!(value.pending_owner, None);
+        let msg = ExecuteMsg::Reset { count: 0 };
+        let res = execute(deps.as_mut(), mock_env(), owner, msg.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("new", &[]), msg).unwrap();
+    }
 }
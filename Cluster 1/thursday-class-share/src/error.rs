@@ -8,6 +8,28 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Counter overflow")]
+    Overflow {},
+
+    #[error("Count {count} is outside the bounds")]
+    OutOfBounds { count: i32 },
+
+    #[error("Minimum is greater than maximum")]
+    InvalidBounds {},
+
+    #[error("No funds sent")]
+    NoFunds {},
+
+    #[error("Expected {expected}{denom} to be sent but received {sent}{denom}")]
+    FundsMismatch {
+        denom: String,
+        expected: u128,
+        sent: u128,
+    },
+
+    #[error("No ownership transfer in progress")]
+    NoPendingOwner {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
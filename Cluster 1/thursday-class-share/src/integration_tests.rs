@@ -2,7 +2,7 @@
 mod tests {
     use crate::helpers::CwTemplateContract;
     use crate::msg::InstantiateMsg;
-    use cosmwasm_std::{Addr, Coin, Empty, Uint128};
+    use cosmwasm_std::{coin, Addr, Coin, Empty, Uint128};
     use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
 
     pub fn contract_template() -> Box<dyn Contract<Empty>> {
@@ -17,6 +17,7 @@ mod tests {
     const USER: &str = "USER";
     const ADMIN: &str = "ADMIN";
     const NATIVE_DENOM: &str = "denom";
+    const OTHER_DENOM: &str = "other";
 
     fn mock_app() -> App {
         AppBuilder::new().build(|router, _, storage| {
@@ -25,10 +26,13 @@ mod tests {
                 .init_balance(
                     storage,
                     &Addr::unchecked(USER),
-                    vec![Coin {
-                        denom: NATIVE_DENOM.to_string(),
-                        amount: Uint128::new(1),
-                    }],
+                    vec![
+                        Coin {
+                            denom: NATIVE_DENOM.to_string(),
+                            amount: Uint128::new(1),
+                        },
+                        coin(500, OTHER_DENOM),
+                    ],
                 )
                 .unwrap();
         })
@@ -38,7 +42,11 @@ mod tests {
         let mut app = mock_app();
         let cw_template_id = app.store_code(contract_template());
 
-        let msg = InstantiateMsg { count: 1i32 };
+        let msg = InstantiateMsg {
+            count: 1i32,
+            min: None,
+            max: None,
+        };
         let cw_template_contract_addr = app
             .instantiate_contract(
                 cw_template_id,
@@ -57,7 +65,7 @@ mod tests {
 
     mod count {
         use super::*;
-        use crate::msg::ExecuteMsg;
+        use crate::msg::{ExecuteMsg, GetCountResponse, QueryMsg};
 
         #[test]
         fn count() {
@@ -66,6 +74,106 @@ mod tests {
             let msg = ExecuteMsg::Increment {};
             let cosmos_msg = cw_template_contract.call(msg).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            // the increment is also credited to the sender
+            let msg = QueryMsg::GetUserCount {
+                address: USER.to_string(),
+            };
+            let res: GetCountResponse = app
+                .wrap()
+                .query_wasm_smart(cw_template_contract.addr(), &msg)
+                .unwrap();
+            assert_eq!(res.count, 1);
+        }
+    }
+
+    mod reflect_funds {
+        use super::*;
+        use crate::msg::ExecuteMsg;
+        use crate::ContractError;
+
+        fn balances(app: &App, addr: &Addr) -> Vec<u128> {
+            [NATIVE_DENOM, OTHER_DENOM]
+                .iter()
+                .map(|denom| {
+                    app.wrap()
+                        .query_balance(addr, *denom)
+                        .unwrap()
+                        .amount
+                        .u128()
+                })
+                .collect()
+        }
+
+        #[test]
+        fn reflects_every_denom() {
+            let (mut app, cw_template_contract) = proper_instantiate();
+
+            let funds = vec![coin(1, NATIVE_DENOM), coin(200, OTHER_DENOM)];
+            let msg = ExecuteMsg::ReflectFunds {
+                amount: funds.clone(),
+            };
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    cw_template_contract.addr(),
+                    &msg,
+                    &funds,
+                )
+                .unwrap();
+            let wasm = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+            assert!(wasm
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "funds" && attr.value == "1denom,200other"));
+
+            // everything sent came straight back
+            assert_eq!(balances(&app, &Addr::unchecked(USER)), vec![1, 500]);
+            assert_eq!(balances(&app, &cw_template_contract.addr()), vec![0, 0]);
+        }
+
+        #[test]
+        fn rejects_no_funds() {
+            let (mut app, cw_template_contract) = proper_instantiate();
+
+            let msg = ExecuteMsg::ReflectFunds { amount: vec![] };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    cw_template_contract.addr(),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(err.downcast().unwrap(), ContractError::NoFunds {}));
+        }
+
+        #[test]
+        fn rejects_mismatched_amount() {
+            let (mut app, cw_template_contract) = proper_instantiate();
+
+            let msg = ExecuteMsg::ReflectFunds {
+                amount: vec![coin(100, OTHER_DENOM)],
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    cw_template_contract.addr(),
+                    &msg,
+                    &[coin(200, OTHER_DENOM)],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::FundsMismatch {
+                    denom,
+                    expected: 100,
+                    sent: 200
+                } if denom == OTHER_DENOM
+            ));
+
+            // the failed call kept nothing
+            assert_eq!(balances(&app, &Addr::unchecked(USER)), vec![1, 500]);
         }
     }
 }
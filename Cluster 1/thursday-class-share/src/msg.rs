@@ -1,8 +1,15 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
+use cosmwasm_std::{Addr, Coin};
+
+use crate::state::{HistoryEntry, Policy};
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub count: i32,
+    // optional lowest and highest values the count may take
+    pub min: Option<i32>,
+    pub max: Option<i32>,
 }
 
 #[cw_serde]
@@ -12,7 +19,18 @@ pub enum ExecuteMsg {
     Reset { count: i32 },
     DecrementBy { count: i32 },
     IncrementBy { count: i32 },
-    ReflectFunds { amount: u128 },
+    // the funds sent are sent straight back, amount has to match them denom by denom
+    ReflectFunds { amount: Vec<Coin> },
+    // owner only, replaces both bounds and requires the current count to be within them
+    UpdateBounds { min: Option<i32>, max: Option<i32> },
+    // owner only, operators may send the messages the policy restricts to operators
+    AddOperator { address: String },
+    RemoveOperator { address: String },
+    // owner only, replaces the permission required by each message
+    UpdatePolicy { policy: Policy },
+    // owner only, the new owner takes over once they accept
+    TransferOwnership { new_owner: String },
+    AcceptOwnership {},
 }
 
 #[cw_serde]
@@ -21,9 +39,27 @@ pub enum QueryMsg {
     // GetCount returns the current count as a json-encoded number
     #[returns(GetCountResponse)]
     GetCount {},
+    // GetUserCount returns the count kept for a single address
+    #[returns(GetCountResponse)]
+    GetUserCount { address: String },
+    // History returns the most recent changes to the count, oldest first
+    #[returns(HistoryResponse)]
+    History {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     // ResetOrNot returns a boolean value if the count has been reset or not
-   // #[returns(bool)]
-    //ResetOrNot {},
+    #[returns(bool)]
+    ResetOrNot {},
+    #[returns(OwnerResponse)]
+    Owner {},
+    #[returns(OperatorsResponse)]
+    Operators {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(Policy)]
+    Policy {},
 }
 
 // We define a custom struct for each query response
@@ -31,3 +67,19 @@ pub enum QueryMsg {
 pub struct GetCountResponse {
     pub count: i32,
 }
+
+#[cw_serde]
+pub struct HistoryResponse {
+    pub entries: Vec<HistoryEntry>,
+}
+
+#[cw_serde]
+pub struct OwnerResponse {
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<Addr>,
+}
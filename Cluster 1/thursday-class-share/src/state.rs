@@ -1,13 +1,65 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Empty};
+use cw_storage_plus::{Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct State {
     pub count: i32,
     pub owner: Addr,
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    // set once the owner has reset the count
+    #[serde(default)]
+    pub reset: bool,
+}
+
+impl State {
+    pub fn in_bounds(&self, count: i32) -> bool {
+        self.min.unwrap_or(i32::MIN) <= count && count <= self.max.unwrap_or(i32::MAX)
+    }
 }
 
 pub const STATE: Item<State> = Item::new("state");
+
+// who may send a message: anyone, an operator (or the owner), or only the owner
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    #[default]
+    Public,
+    Operators,
+    Owner,
+}
+
+// the permission required by each message that moves the count; Reset is always owner only
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct Policy {
+    pub increment: Permission,
+    pub decrement: Permission,
+    pub increment_by: Permission,
+    pub decrement_by: Permission,
+}
+
+// a single change to the global count, delta is wide enough to hold any reset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub sender: Addr,
+    pub delta: i64,
+    pub height: u64,
+}
+
+// how many of the most recent changes are kept in HISTORY
+pub const HISTORY_SIZE: u64 = 100;
+
+// each address' own count, moved by the same deltas it applies to the global count
+pub const USER_COUNTS: Map<&Addr, i32> = Map::new("user_counts");
+pub const HISTORY: Map<u64, HistoryEntry> = Map::new("history");
+pub const HISTORY_SEQ: Item<u64> = Item::new("history_seq");
+
+pub const POLICY: Item<Policy> = Item::new("policy");
+pub const OPERATORS: Map<&Addr, Empty> = Map::new("operators");
+// set by the owner and cleared once the new owner accepts
+pub const PENDING_OWNER: Item<Addr> = Item::new("pending_owner");
@@ -9,27 +9,58 @@ use cosmwasm_std::entry_point;
 
 // import required packages from cosmwasm_std library
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    coins, from_binary, to_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
 };
 
-// import set_contract_version from cw2 library
-use cw2::set_contract_version;
+// import Bound from cw_storage_plus library so the listing queries can start after a given key
+use cw_storage_plus::Bound;
+
+// import Expiration from cw_utils library so polls can be closed early, and the payment helpers to check creation fees
+use cw_utils::{must_pay, nonpayable, Expiration, PaymentError};
+
+// import set_contract_version and get_contract_version from cw2 library
+use cw2::{get_contract_version, set_contract_version};
+
+// import Version from semver library so migrate can compare contract versions
+use semver::Version;
+
+// import the cw4 and cw20 contract helpers so vote weights can be read from external contracts
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, Denom};
+use cw4::Cw4Contract;
 
 // import required dependencies from state file
-use crate::state::{Ballot, Config, Poll, BALLOTS, CONFIG, POLLS};
+use crate::state::{
+    ballots, polls, Ballot, CommitReveal, Config, Poll, PollFee, PollOption, PollStatus, PollType,
+    WeightSource, COMMITMENTS, CONFIG, LEGACY_BALLOTS, LEGACY_CONFIG, LEGACY_POLLS, TREASURY,
+};
+
+// import vote_commitment from helpers file so revealed votes can be checked against their commitment
+use crate::helpers::vote_commitment;
 
 // import required dependencies from error file
 use crate::error::ContractError;
 
 // import required dependencies from msg file
 use crate::msg::{
-    AllPollsResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, VoteResponse,
+    AllPollsResponse, BallotsResponse, CommitCountResponse, CommitmentInfo, CommitmentsResponse,
+    CreatePollMsg, Cw20SnapshotQueryMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OptionRef,
+    PollInfo, PollResponse, QueryMsg, ReceiveMsg, ResultsResponse, RoundResponse, VoteResponse,
+    WeightSourceMsg,
 };
 
 // set contract name and version as constants
 const CONTRACT_NAME: &str = "crates.io:cw-starter";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// set the default and maximum page sizes of the listing queries as constants
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// set the default option limit of a poll and the longest allowed question as constants
+const DEFAULT_MAX_OPTIONS: u32 = 10;
+const MAX_QUESTION_LENGTH: usize = 256;
+
 // define instantiate function as contract's first entrypoint
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate( 
@@ -42,10 +73,21 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?; // set contract version and check for errors
     let admin = msg.admin.unwrap_or_else(|| info.sender.to_string()); // set admin to Addr of the message sender if no admin is provided when the message is sent
     let validated_admin = deps.api.addr_validate(&admin)?; // validate the admin address and check for errors
+    let creation_fee = match msg.creation_fee { // validate the creation fee, a zero fee is the same as no fee
+        Some(fee) if fee.amount.is_zero() => None,
+        Some(PollFee { denom: Denom::Cw20(token), amount }) => Some(PollFee {
+            denom: Denom::Cw20(deps.api.addr_validate(token.as_str())?), // validate the cw20 token address and check for errors
+            amount,
+        }),
+        fee => fee,
+    };
     let config = Config { // define the contract's config as a Config struct
         admin: validated_admin.clone(), // set the admin to the validated admin address as a clone, so the reference is not lost of the original admin variable
+        creation_fee, // set the validated creation fee
+        max_options: msg.max_options.unwrap_or(DEFAULT_MAX_OPTIONS), // set the option limit, 10 unless the message sets one
     };
     CONFIG.save(deps.storage, &config)?; // save the config to the contract's storage and check for errors
+    TREASURY.save(deps.storage, &Uint128::zero())?; // start with no collected fees
     Ok(Response::new() // define the response if the instantiate function is successful and add new attributes to the metadata
         .add_attribute("action", "instantiate") // lets the client know the action that was performed was an instantiation
         .add_attribute("admin", validated_admin.to_string())) // lets the client know the admin address
@@ -60,103 +102,661 @@ pub fn execute( // define required inputs to the execute function
     msg: ExecuteMsg, // message being sent to contract
 ) -> Result<Response, ContractError> { // define the result response of the execute function and any errors that may occur
     match msg { // match the message being sent to the execute function
-        ExecuteMsg::CreatePoll { // if the message is a CreatePoll message, then look for the following required inputs to the message so it can execute
-            poll_id, // poll id
-            question, // poll question
-            options, // poll answer/choice options
-        } => execute_create_poll(deps, env, info, poll_id, question, options), // execute the create_poll_function using the required inputs
-        ExecuteMsg::Vote { poll_id, vote } => execute_vote(deps, env, info, poll_id, vote), // if the message is a Vote message, then look for the following required inputs to the message so it can execute
+        ExecuteMsg::CreatePoll(create_poll_msg) => execute_create_poll(deps, env, info, create_poll_msg), // if the message is a CreatePoll message, then check the native creation fee and create the poll it carries
+        ExecuteMsg::Receive(receive_msg) => execute_receive(deps, env, info, receive_msg), // if the message is a Receive message, then check the cw20 creation fee and handle the message sent with the tokens
+        ExecuteMsg::Vote { poll_id, vote } => execute_vote(deps, env, info, poll_id, vec![vote]), // if the message is a Vote message, then look for the following required inputs to the message so it can execute
+        ExecuteMsg::CastBallot { poll_id, choices } => execute_vote(deps, env, info, poll_id, choices), // if the message is a CastBallot message, then vote for every chosen option
+        ExecuteMsg::CommitVote { poll_id, commitment } => execute_commit_vote(deps, env, info, poll_id, commitment), // if the message is a CommitVote message, then store the hidden vote of a commit-reveal poll
+        ExecuteMsg::RevealVote { poll_id, vote, salt } => execute_reveal_vote(deps, env, info, poll_id, vote, salt), // if the message is a RevealVote message, then count the vote if it matches its commitment
+        ExecuteMsg::ClosePoll { poll_id } => execute_close_poll(deps, env, info, poll_id), // if the message is a ClosePoll message, then stop the poll accepting votes
+        ExecuteMsg::DeletePoll { poll_id } => execute_delete_poll(deps, env, info, poll_id), // if the message is a DeletePoll message, then remove the poll if nobody has voted
+        ExecuteMsg::EditPoll { poll_id, question, options } => execute_edit_poll(deps, env, info, poll_id, question, options), // if the message is an EditPoll message, then replace the question and/or options if nobody has voted
+        ExecuteMsg::UpdateAdmin { admin } => execute_update_admin(deps, env, info, admin), // if the message is an UpdateAdmin message, then hand the admin role to the new address
+        ExecuteMsg::WithdrawFees { recipient } => execute_withdraw_fees(deps, env, info, recipient), // if the message is a WithdrawFees message, then send the collected fees to the recipient
     }
 }
 
-// define the execute_create_poll function which is called by the execute function 
+// define the execute_create_poll function which is called by the execute function and creates a poll paid for with native funds
 fn execute_create_poll( // define required inputs to the execute_create_poll function
     deps: DepsMut, // mutable contract state
-    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
     info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
-    poll_id: String, // poll id as a String
-    question: String, // poll question as a String
-    options: Vec<String>, // poll answer/choice options as a Vec of Strings
+    msg: CreatePollMsg, // poll id, question, options and optional voting window of the new poll
 ) -> Result<Response, ContractError> { // define the result response of the execute_create_poll function and any errors that may occur
-    if options.len() > 10 { // if the number of options is greater than 10, then return an error
-        return Err(ContractError::TooManyOptions {}); // return the TooManyOptions error if the conditional line above is true
+    let config = CONFIG.load(deps.storage)?; // load the config to find the creation fee
+    match config.creation_fee {
+        None => nonpayable(&info)?, // no fee, so no funds may be sent either
+        Some(PollFee { denom: Denom::Native(denom), amount }) => {
+            // the sender must pay exactly the fee in the fee denom
+            if must_pay(&info, &denom)? != amount {
+                return Err(ContractError::InvalidFee { denom, amount });
+            }
+            collect_fee(deps.storage, amount)?;
+        }
+        Some(fee) => return Err(invalid_fee(fee)), // cw20 fees must be paid through Receive
+    }
+
+    create_poll(deps, env, info.sender, msg)
+}
+
+// define the execute_receive function which is called by the execute function when cw20 tokens are sent to the contract
+fn execute_receive( // define required inputs to the execute_receive function
+    deps: DepsMut, // mutable contract state
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    info: MessageInfo, // external enviroment info, the sender is the cw20 token contract
+    wrapper: Cw20ReceiveMsg, // the original sender, the amount of tokens sent and the message sent with them
+) -> Result<Response, ContractError> { // define the result response of the execute_receive function and any errors that may occur
+    let config = CONFIG.load(deps.storage)?; // load the config to find the creation fee
+    match config.creation_fee {
+        None => return Err(PaymentError::NonPayable {}.into()), // no fee, so no tokens should be sent
+        // the tokens must come from the fee token contract and be exactly the fee
+        Some(PollFee { denom: Denom::Cw20(token), amount })
+            if token == info.sender && wrapper.amount == amount => {}
+        Some(fee) => return Err(invalid_fee(fee)),
+    }
+    collect_fee(deps.storage, wrapper.amount)?;
+
+    // the poll creator is whoever sent the tokens, not the token contract
+    let creator = deps.api.addr_validate(&wrapper.sender)?;
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::CreatePoll(msg) => create_poll(deps, env, creator, msg),
+    }
+}
+
+// define the collect_fee function which adds a paid creation fee to the treasury
+fn collect_fee(storage: &mut dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    TREASURY.update(storage, |treasury| -> StdResult<_> { Ok(treasury + amount) })
+}
+
+// define the invalid_fee function which builds the error telling the sender which fee to pay
+fn invalid_fee(fee: PollFee) -> ContractError {
+    let denom = match fee.denom {
+        Denom::Native(denom) => denom,
+        Denom::Cw20(token) => token.to_string(),
+    };
+    ContractError::InvalidFee {
+        denom,
+        amount: fee.amount,
+    }
+}
+
+// define the create_poll function which validates and saves a new poll once its creation fee is settled
+fn create_poll( // define required inputs to the create_poll function
+    deps: DepsMut, // mutable contract state
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    creator: Addr, // address creating the poll
+    msg: CreatePollMsg, // poll id, question, options and optional voting window of the new poll
+) -> Result<Response, ContractError> { // define the result response of the create_poll function and any errors that may occur
+    let CreatePollMsg { // destructure the message into its fields
+        poll_id,
+        question,
+        options,
+        start,
+        end,
+        weight_source,
+        poll_type,
+        reveal_end,
+    } = msg;
+
+    // saving over an existing poll would orphan its ballots and corrupt the tallies
+    if polls().may_load(deps.storage, &poll_id)?.is_some() {
+        return Err(ContractError::PollAlreadyExists { poll_id });
     }
 
-    // create mutable poll options as a vector of tuples of a pair of Strings and u64s
-    let mut opts: Vec<(String, u64)> = vec![];
-    for option in options { // for each option in the options vector
-        opts.push((option, 0)); // push the option and a 0 to the opts vector
+    let config = CONFIG.load(deps.storage)?; // load the config to find the option limit
+    validate_question(&question)?; // check the question is not blank or too long
+    let options = validate_options(options, &poll_type, config.max_options)?; // trim the options and check they are unique and fit the option limit and poll type
+
+    if let Some(end) = end {
+        // a poll that has already ended could never receive a vote
+        if end.is_expired(&env.block) {
+            return Err(ContractError::InvalidVotingWindow {});
+        }
+        // the poll must close after it opens, expirations of different kinds (height vs time) cannot be compared so they are allowed
+        if let Some(start) = start {
+            if start >= end {
+                return Err(ContractError::InvalidVotingWindow {});
+            }
+        }
     }
 
+    // a commit-reveal poll takes commitments until end and reveals from end until reveal_end
+    let commit_reveal = match reveal_end {
+        None => None,
+        Some(reveal_end) => {
+            if poll_type != PollType::SingleChoice {
+                return Err(ContractError::CommitRevealSingleChoice {});
+            }
+            // the reveal phase must come after a commit phase that ends, incomparable expirations are allowed as above
+            let end = end.ok_or(ContractError::InvalidVotingWindow {})?;
+            if reveal_end <= end {
+                return Err(ContractError::InvalidVotingWindow {});
+            }
+            Some(CommitReveal {
+                reveal_end,
+                commits: 0,
+                reveals: 0,
+            })
+        }
+    };
+
+    // validate the weight source address and snapshot weights at the current height
+    let weight_source = match weight_source {
+        None => None, // no weight source, every ballot counts as 1
+        Some(WeightSourceMsg::Cw4Group { addr }) => Some(WeightSource::Cw4Group {
+            addr: deps.api.addr_validate(&addr)?,
+            height: env.block.height,
+        }),
+        Some(WeightSourceMsg::Cw20Balance { addr }) => {
+            let addr = deps.api.addr_validate(&addr)?;
+            // current balances could be moved to another address and vote twice, so refuse tokens without snapshots
+            let snapshot: StdResult<BalanceResponse> = deps.querier.query_wasm_smart(
+                &addr,
+                &Cw20SnapshotQueryMsg::BalanceAtHeight {
+                    address: creator.to_string(),
+                    height: env.block.height,
+                },
+            );
+            if snapshot.is_err() {
+                return Err(ContractError::Cw20NoSnapshot {
+                    addr: addr.to_string(),
+                });
+            }
+            Some(WeightSource::Cw20Balance {
+                addr,
+                height: env.block.height,
+            })
+        }
+    };
+
     // create a poll struct
     let poll = Poll { 
-        creator: info.sender, // set the poll creator to the message sender's address 
+        creator, // set the poll creator to the address that paid for the poll
         question, // ingest the poll question from the message
-        options: opts, // ingest the poll options from the message
+        options: number_options(options), // ingest the poll options from the message, each numbered and starting with no votes
+        start, // ingest the optional opening expiration from the message
+        end, // ingest the optional closing expiration from the message
+        weight_source, // ingest the validated weight source from the message
+        poll_type, // ingest the poll type from the message
+        commit_reveal, // ingest the optional reveal phase from the message, with no votes committed yet
     };
 
     // save the poll to the contract's state and check for errors
-    POLLS.save(deps.storage, &poll_id, &poll)?;
+    polls().save(deps.storage, &poll_id, &poll)?;
 
     Ok(Response::new()) // return a response if the execute_create_poll function is successful
 }
 
+// define the validate_question function which checks a poll's question is not blank or too long
+fn validate_question(question: &str) -> Result<(), ContractError> {
+    if question.trim().is_empty() {
+        return Err(ContractError::EmptyQuestion {});
+    }
+    if question.chars().count() > MAX_QUESTION_LENGTH {
+        return Err(ContractError::QuestionTooLong {
+            max: MAX_QUESTION_LENGTH,
+        });
+    }
+    Ok(())
+}
+
+// define the validate_options function which trims a poll's options and checks them against the option limit and its poll type
+fn validate_options(options: Vec<String>, poll_type: &PollType, max_options: u32) -> Result<Vec<String>, ContractError> {
+    if options.len() > max_options as usize { // if the number of options is greater than the configured limit, then return an error
+        return Err(ContractError::TooManyOptions {}); // return the TooManyOptions error if the conditional line above is true
+    }
+
+    // surrounding whitespace is dropped so "Yes" and " Yes" are the same option
+    let options: Vec<String> = options.iter().map(|option| option.trim().to_string()).collect();
+    for (i, option) in options.iter().enumerate() {
+        // every option needs some text to vote for
+        if option.is_empty() {
+            return Err(ContractError::EmptyOption {});
+        }
+        // and must differ from the options before it ignoring case, otherwise "Yes" and "yes" would split the vote
+        if options[..i].iter().any(|other| other.to_lowercase() == option.to_lowercase()) {
+            return Err(ContractError::DuplicateOption {
+                option: option.clone(),
+            });
+        }
+    }
+
+    // an approval poll must allow at least one choice and no more choices than there are options
+    if let PollType::Approval { max_choices } = *poll_type {
+        if max_choices == 0 || max_choices as usize > options.len() {
+            return Err(ContractError::InvalidMaxChoices {});
+        }
+    }
+    Ok(options)
+}
+
+// define the number_options function which gives every option its id and an empty tally
+fn number_options(options: Vec<String>) -> Vec<PollOption> {
+    // create mutable poll options as a vector of PollOptions
+    let mut opts: Vec<PollOption> = vec![];
+    for (id, text) in options.into_iter().enumerate() { // for each option in the options vector, numbered from 0
+        opts.push(PollOption {
+            id: id as u32,
+            text,
+            votes: Uint128::zero(),
+        }); // push the numbered option with a zero tally to the opts vector
+    }
+    opts
+}
+
 // define the execute_vote function which is called by the execute function
 fn execute_vote( // define required inputs to the execute_vote function
     deps: DepsMut, // mutable contract state
-    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
     info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
     poll_id: String, // poll id as a String
-    vote: String, // message sender's vote as a String
+    choices: Vec<OptionRef>, // message sender's chosen options by id or text, in order of preference for ranked-choice polls
 ) -> Result<Response, ContractError> { // define the result response of the execute_vote function and any errors that may occur
-    let poll = POLLS.may_load(deps.storage, &poll_id)?; // load the poll from the contract's state and check for errors
+    let poll = polls().may_load(deps.storage, &poll_id)?; // load the poll from the contract's state and check for errors
 
     // define the match statement to update the ballot with the sender's vote 
     match poll {
         Some(mut poll) => {
-            // The poll exists
-            BALLOTS.update( // update the ballot with the sender's vote
-                deps.storage, // update contract state
-                (info.sender, &poll_id), // update the ballot with the sender's address and the poll id
-                |ballot| -> StdResult<Ballot> { 
-                    match ballot {
-                        Some(ballot) => {
-                            // We need to revoke their old vote
-                            // Find the position
-                            let position_of_old_vote = poll
-                                .options
-                                .iter() // iterate through the poll options
-                                .position(|option| option.0 == ballot.option) // find the position of the old vote
-                                .unwrap(); // unwrap the position of the old vote
-                            // Decrement by 1
-                            poll.options[position_of_old_vote].1 -= 1; // decrement the old vote by 1
-                            // Update the ballot
-                            Ok(Ballot { 
-                                option: vote.clone(), // clone the sender's vote
-                            })
-                        }
-                        None => { // if the ballot does not exist
-                            // Simply add the ballot
-                            Ok(Ballot {
-                                option: vote.clone(), // clone the sender's vote
-                            })
-                        }
-                    }
-                },
-            )?;
+            // The poll exists, only accept the vote while it is inside its voting window
+            match poll.status(&env.block) {
+                PollStatus::Upcoming => return Err(ContractError::PollNotStarted {}),
+                PollStatus::Revealing | PollStatus::Closed => return Err(ContractError::PollClosed {}),
+                PollStatus::Open => {}
+            }
+            // commit-reveal polls only take votes through CommitVote and RevealVote
+            if poll.commit_reveal.is_some() {
+                return Err(ContractError::CommitRevealPoll {});
+            }
 
-            // Find the position of the new vote option and increment it by 1
-            let position = poll.options.iter().position(|option| option.0 == vote);
-            if position.is_none() { // if the position of the new vote option is none, then return an error
-                return Err(ContractError::Unauthorized {}); 
+            // look up the id of every choice, then check the choices fit the poll type
+            let choices = resolve_choices(&poll, choices)?;
+            validate_choices(&poll, &choices)?;
+
+            // look up how much the sender's vote is worth in this poll
+            let weight = voting_weight(deps.as_ref(), &poll, &info.sender)?;
+            if weight.is_zero() {
+                return Err(ContractError::NoVotingPower {});
             }
-            let position = position.unwrap(); // unwrap the position of the new vote option
-            poll.options[position].1 += 1; // increment the new vote option by 1
 
-            POLLS.save(deps.storage, &poll_id, &poll)?; // save the poll to the contract's state and check for errors
+            record_ballot(deps.storage, &poll_id, &mut poll, info.sender, choices, weight)?; // count the ballot and save it with the poll
             Ok(Response::new()) // return a response if the execute_vote function is successful
         }
-        None => Err(ContractError::Unauthorized {}), // The poll does not exist so we just error
+        None => Err(ContractError::PollNotFound { poll_id }), // The poll does not exist so we just error
+    }
+}
+
+// define the record_ballot function which counts a voter's ballot in a poll and saves both, replacing any ballot the voter cast before
+fn record_ballot( // define required inputs to the record_ballot function
+    storage: &mut dyn Storage, // mutable contract storage
+    poll_id: &str, // poll id of the poll being voted in
+    poll: &mut Poll, // the poll being voted in, its tallies are updated in place
+    voter: Addr, // address casting the ballot
+    choices: Vec<u32>, // validated option ids of the ballot
+    weight: Uint128, // how much the ballot counts for
+) -> Result<(), ContractError> { // define the result of the record_ballot function and any errors that may occur
+    // If the voter already voted we need to revoke their old vote using the weight it was counted with
+    let key = (voter, poll_id);
+    if let Some(old_ballot) = ballots().may_load(storage, key.clone())? {
+        for id in counted_choices(&poll.poll_type, &old_ballot.options) {
+            if let Some(option) = poll.options.iter_mut().find(|option| &option.id == id) {
+                option.votes = option.votes.checked_sub(old_ballot.weight)?; // decrement the old vote by its recorded weight
+            }
+        }
+    }
+
+    // increment every counted option of the new vote by the voter's weight
+    for id in counted_choices(&poll.poll_type, &choices) {
+        if let Some(option) = poll.options.iter_mut().find(|option| &option.id == id) {
+            option.votes = option.votes.checked_add(weight)?;
+        }
+    }
+
+    // save the ballot, recording the weight so a later re-vote can subtract it
+    let ballot = Ballot {
+        poll_id: poll_id.to_string(),
+        options: choices,
+        weight,
+    };
+    ballots().save(storage, key, &ballot)?;
+
+    polls().save(storage, poll_id, poll)?; // save the poll to the contract's state and check for errors
+    Ok(())
+}
+
+// define the execute_commit_vote function which is called by the execute function and stores a hidden vote in a commit-reveal poll
+fn execute_commit_vote( // define required inputs to the execute_commit_vote function
+    deps: DepsMut, // mutable contract state
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
+    poll_id: String, // poll id as a String
+    commitment: Binary, // sha256 of the sender's address, poll id, chosen option id and salt, see helpers::vote_commitment
+) -> Result<Response, ContractError> { // define the result response of the execute_commit_vote function and any errors that may occur
+    let mut poll = load_poll(deps.as_ref(), &poll_id)?;
+    if poll.commit_reveal.is_none() {
+        return Err(ContractError::NotCommitRevealPoll {});
+    }
+    // commitments are only taken while the poll is open
+    match poll.status(&env.block) {
+        PollStatus::Upcoming => return Err(ContractError::PollNotStarted {}),
+        PollStatus::Revealing | PollStatus::Closed => return Err(ContractError::PollClosed {}),
+        PollStatus::Open => {}
+    }
+    if commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+    // reject voters with no weight now rather than at reveal
+    if voting_weight(deps.as_ref(), &poll, &info.sender)?.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    // a voter may replace their commitment until the poll ends, only their first one adds to the count
+    let key = (poll_id.as_str(), &info.sender);
+    if !COMMITMENTS.has(deps.storage, key) {
+        if let Some(commit_reveal) = poll.commit_reveal.as_mut() {
+            commit_reveal.commits += 1;
+        }
+        polls().save(deps.storage, &poll_id, &poll)?;
+    }
+    COMMITMENTS.save(deps.storage, key, &commitment)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "commit_vote")
+        .add_attribute("poll_id", poll_id))
+}
+
+// define the execute_reveal_vote function which is called by the execute function and counts a committed vote once its option and salt are revealed
+fn execute_reveal_vote( // define required inputs to the execute_reveal_vote function
+    deps: DepsMut, // mutable contract state
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
+    poll_id: String, // poll id as a String
+    vote: OptionRef, // the committed option by id or text
+    salt: String, // the salt the commitment was built with
+) -> Result<Response, ContractError> { // define the result response of the execute_reveal_vote function and any errors that may occur
+    let mut poll = load_poll(deps.as_ref(), &poll_id)?;
+    if poll.commit_reveal.is_none() {
+        return Err(ContractError::NotCommitRevealPoll {});
+    }
+    // reveals are only taken between the end of the commit phase and reveal_end
+    match poll.status(&env.block) {
+        PollStatus::Upcoming | PollStatus::Open => return Err(ContractError::RevealNotStarted {}),
+        PollStatus::Closed => return Err(ContractError::PollClosed {}),
+        PollStatus::Revealing => {}
+    }
+
+    let key = (poll_id.as_str(), &info.sender);
+    let commitment = COMMITMENTS
+        .may_load(deps.storage, key)?
+        .ok_or(ContractError::NoCommitment {})?;
+    let choices = resolve_choices(&poll, vec![vote])?;
+    if vote_commitment(&info.sender, &poll_id, choices[0], &salt) != commitment {
+        return Err(ContractError::CommitmentMismatch {});
+    }
+    let weight = voting_weight(deps.as_ref(), &poll, &info.sender)?;
+    if weight.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    // the commitment is used up, so it cannot be revealed twice and is not listed as unrevealed
+    COMMITMENTS.remove(deps.storage, key);
+    if let Some(commit_reveal) = poll.commit_reveal.as_mut() {
+        commit_reveal.reveals += 1;
+    }
+    record_ballot(deps.storage, &poll_id, &mut poll, info.sender, choices, weight)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reveal_vote")
+        .add_attribute("poll_id", poll_id))
+}
+
+// define the resolve_choices function which turns every choice into the id of the poll option it names
+fn resolve_choices(poll: &Poll, choices: Vec<OptionRef>) -> Result<Vec<u32>, ContractError> {
+    choices
+        .into_iter()
+        .map(|choice| {
+            let found = match &choice {
+                OptionRef::Id(id) => poll.options.iter().find(|option| &option.id == id),
+                OptionRef::Text(text) => poll.options.iter().find(|option| &option.text == text),
+            };
+            // every choice must be one of the poll's options
+            found.map(|option| option.id).ok_or_else(|| ContractError::InvalidOption {
+                option: match choice {
+                    OptionRef::Id(id) => id.to_string(),
+                    OptionRef::Text(text) => text,
+                },
+            })
+        })
+        .collect()
+}
+
+// define the validate_choices function which checks a ballot's choices against the poll's type
+fn validate_choices(poll: &Poll, choices: &[u32]) -> Result<(), ContractError> {
+    if choices.is_empty() {
+        return Err(ContractError::NoChoices {});
+    }
+
+    // work out how many choices the poll type allows, ranked-choice polls may rank every option
+    let max = match poll.poll_type {
+        PollType::SingleChoice => 1,
+        PollType::Approval { max_choices } => max_choices,
+        PollType::RankedChoice => poll.options.len() as u32,
+    };
+    if choices.len() as u32 > max {
+        return Err(ContractError::TooManyChoices { max });
+    }
+
+    for (i, choice) in choices.iter().enumerate() {
+        // every option may only be chosen once
+        if choices[..i].contains(choice) {
+            return Err(ContractError::DuplicateChoice { option: *choice });
+        }
+    }
+    Ok(())
+}
+
+// define the counted_choices function which returns the choices of a ballot that count toward Poll.options
+// ranked-choice ballots only count toward their first preference, the lower preferences are used by the instant-runoff in query_results
+fn counted_choices<'a>(poll_type: &PollType, choices: &'a [u32]) -> &'a [u32] {
+    match poll_type {
+        PollType::RankedChoice => &choices[..choices.len().min(1)],
+        _ => choices,
+    }
+}
+
+// define the execute_close_poll function which is called by the execute function and ends a poll early
+fn execute_close_poll( // define required inputs to the execute_close_poll function
+    deps: DepsMut, // mutable contract state
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
+    poll_id: String, // poll id as a String
+) -> Result<Response, ContractError> { // define the result response of the execute_close_poll function and any errors that may occur
+    let mut poll = load_managed_poll(deps.as_ref(), &info, &poll_id)?; // load the poll and check the sender may manage it
+
+    if matches!(poll.status(&env.block), PollStatus::Revealing | PollStatus::Closed) { // a poll can only be closed once
+        return Err(ContractError::PollClosed {});
+    }
+
+    // end the poll at the current height so no further votes are accepted
+    poll.end = Some(Expiration::AtHeight(env.block.height));
+    polls().save(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "close_poll")
+        .add_attribute("poll_id", poll_id))
+}
+
+// define the execute_delete_poll function which is called by the execute function and removes a poll nobody has voted in
+fn execute_delete_poll( // define required inputs to the execute_delete_poll function
+    deps: DepsMut, // mutable contract state
+    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
+    poll_id: String, // poll id as a String
+) -> Result<Response, ContractError> { // define the result response of the execute_delete_poll function and any errors that may occur
+    load_managed_poll(deps.as_ref(), &info, &poll_id)?; // load the poll and check the sender may manage it
+    ensure_no_votes(deps.as_ref(), &poll_id)?; // deleting would lose the ballots already cast
+
+    polls().remove(deps.storage, &poll_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "delete_poll")
+        .add_attribute("poll_id", poll_id))
+}
+
+// define the execute_edit_poll function which is called by the execute function and changes a poll's question and/or options before the first vote
+fn execute_edit_poll( // define required inputs to the execute_edit_poll function
+    deps: DepsMut, // mutable contract state
+    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
+    poll_id: String, // poll id as a String
+    question: Option<String>, // new question, None keeps the current one
+    options: Option<Vec<String>>, // new options, None keeps the current ones
+) -> Result<Response, ContractError> { // define the result response of the execute_edit_poll function and any errors that may occur
+    let mut poll = load_managed_poll(deps.as_ref(), &info, &poll_id)?; // load the poll and check the sender may manage it
+    ensure_no_votes(deps.as_ref(), &poll_id)?; // editing after a vote would change what voters agreed to
+
+    if let Some(question) = question {
+        validate_question(&question)?;
+        poll.question = question;
+    }
+    if let Some(options) = options {
+        let config = CONFIG.load(deps.storage)?;
+        let options = validate_options(options, &poll.poll_type, config.max_options)?; // the new options must still fit the option limit and poll type
+        poll.options = number_options(options); // renumber from 0, nobody has voted so no ballot refers to the old ids
+    }
+    polls().save(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "edit_poll")
+        .add_attribute("poll_id", poll_id))
+}
+
+// define the execute_update_admin function which is called by the execute function and hands the admin role to a new address
+fn execute_update_admin( // define required inputs to the execute_update_admin function
+    deps: DepsMut, // mutable contract state
+    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
+    admin: String, // new admin address as a String
+) -> Result<Response, ContractError> { // define the result response of the execute_update_admin function and any errors that may occur
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin { // only the current admin can hand over the role
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.admin = deps.api.addr_validate(&admin)?; // validate the new admin address and check for errors
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_admin")
+        .add_attribute("admin", config.admin))
+}
+
+// define the execute_withdraw_fees function which is called by the execute function and sends every collected creation fee to a recipient
+fn execute_withdraw_fees( // define required inputs to the execute_withdraw_fees function
+    deps: DepsMut, // mutable contract state
+    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    info: MessageInfo, // external enviroment info such as sender address, sent funds, etc..
+    recipient: String, // address to send the fees to as a String
+) -> Result<Response, ContractError> { // define the result response of the execute_withdraw_fees function and any errors that may occur
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin { // only the admin can withdraw fees
+        return Err(ContractError::Unauthorized {});
+    }
+    let recipient = deps.api.addr_validate(&recipient)?; // validate the recipient address and check for errors
+
+    let amount = TREASURY.load(deps.storage)?;
+    // fees are only collected while a fee is configured, so the configured denom is the one they were paid in
+    let fee = match config.creation_fee {
+        Some(fee) if !amount.is_zero() => fee,
+        _ => return Err(ContractError::NoFeesToWithdraw {}),
+    };
+    TREASURY.save(deps.storage, &Uint128::zero())?; // empty the treasury
+
+    // build the payout in the same kind of payment the fees were collected in
+    let payout: CosmosMsg = match fee.denom {
+        Denom::Native(denom) => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(amount.u128(), denom),
+        }
+        .into(),
+        Denom::Cw20(token) => WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    Ok(Response::new()
+        .add_message(payout)
+        .add_attribute("action", "withdraw_fees")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount))
+}
+
+// define the load_poll function which loads a poll or errors with PollNotFound
+fn load_poll(deps: Deps, poll_id: &str) -> Result<Poll, ContractError> {
+    polls()
+        .may_load(deps.storage, poll_id)?
+        .ok_or_else(|| ContractError::PollNotFound {
+            poll_id: poll_id.to_string(),
+        })
+}
+
+// define the load_managed_poll function which loads a poll and checks the sender is its creator or the contract admin
+fn load_managed_poll(deps: Deps, info: &MessageInfo, poll_id: &str) -> Result<Poll, ContractError> {
+    let poll = load_poll(deps, poll_id)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != poll.creator && info.sender != config.admin {
+        return Err(ContractError::NotCreatorOrAdmin {});
+    }
+    Ok(poll)
+}
+
+// define the ensure_no_votes function which errors if any ballot has been cast or any vote committed in a poll
+fn ensure_no_votes(deps: Deps, poll_id: &str) -> Result<(), ContractError> {
+    let has_ballots = ballots()
+        .idx
+        .poll
+        .prefix(poll_id.to_string())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    let has_commitments = COMMITMENTS
+        .prefix(poll_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    let has_votes = has_ballots || has_commitments;
+    if has_votes {
+        return Err(ContractError::PollHasVotes {});
+    }
+    Ok(())
+}
+
+// define the voting_weight function which works out how much a voter's ballot counts for in a poll
+fn voting_weight(deps: Deps, poll: &Poll, voter: &Addr) -> StdResult<Uint128> {
+    match &poll.weight_source {
+        None => Ok(Uint128::new(1)), // every ballot counts as 1
+        Some(WeightSource::Cw4Group { addr, height }) => {
+            // use the voter's group weight at the poll's creation height, non-members have no weight
+            let weight = Cw4Contract(addr.clone()).is_member(&deps.querier, voter, Some(*height))?;
+            Ok(Uint128::from(weight.unwrap_or_default()))
+        }
+        Some(WeightSource::Cw20Balance { addr, height }) => {
+            // use the voter's token balance at the poll's creation height
+            let res: BalanceResponse = deps.querier.query_wasm_smart(
+                addr,
+                &Cw20SnapshotQueryMsg::BalanceAtHeight {
+                    address: voter.to_string(),
+                    height: *height,
+                },
+            )?;
+            Ok(res.balance)
+        }
     }
 }
 
@@ -168,33 +768,85 @@ pub fn query( // define required inputs to the query function
     msg: QueryMsg) // query message being passed to the query function 
     -> StdResult<Binary> { // define return result of the query function as binary data
     match msg { // match the query message to the query function
-        QueryMsg::AllPolls {} => query_all_polls(deps, env), // query all polls message which takes storage and enviroment info as inputs
+        QueryMsg::AllPolls { start_after, limit } => query_all_polls(deps, env, start_after, limit), // query all polls message which takes storage, enviroment info, and pagination as inputs
+        QueryMsg::PollsByCreator { creator, start_after, limit } => query_polls_by_creator(deps, env, creator, start_after, limit), // query a specific creator's polls which takes storage, enviroment info, creator address, and pagination as inputs
         QueryMsg::Poll { poll_id } => query_poll(deps, env, poll_id), // query poll message which takes storage, enviroment info, and poll id as inputs
         QueryMsg::Vote { address, poll_id } => query_vote(deps, env, address, poll_id), // query a specific address' vote of a specific poll which takes storage, enviroment info, address, and poll id as inputs
+        QueryMsg::BallotsByVoter { voter, start_after, limit } => query_ballots_by_voter(deps, env, voter, start_after, limit), // query every ballot of a specific voter which takes storage, enviroment info, voter address, and pagination as inputs
+        QueryMsg::Results { poll_id } => query_results(deps, env, poll_id), // query the counted results of a specific poll which takes storage, enviroment info, and poll id as inputs
+        QueryMsg::CommitCount { poll_id } => query_commit_count(deps, env, poll_id), // query how many votes of a commit-reveal poll were committed and revealed which takes storage, enviroment info, and poll id as inputs
+        QueryMsg::UnrevealedCommitments { poll_id, start_after, limit } => query_unrevealed_commitments(deps, env, poll_id, start_after, limit), // query the commitments never revealed in a finished commit-reveal poll which takes storage, enviroment info, poll id, and pagination as inputs
     }
 }
 
-// define the query_all_polls function which is called by the query function and returns all polls in the contract's state
+// define the query_all_polls function which is called by the query function and returns a page of the polls in the contract's state
 fn query_all_polls( // define required inputs to the query_all_polls function
     deps: Deps, // immutable contract state because we are just reading data, not writing data
-    _env: Env) // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    start_after: Option<String>, // poll id to start the page after, None starts from the first poll
+    limit: Option<u32>) // maximum number of polls to return
     -> StdResult<Binary> { // define return result of the query_all_polls function as binary data
-    let polls = POLLS // load the polls from the contract's state
-        .range(deps.storage, None, None, Order::Ascending) // iterate through the polls and order them in ascending order
-        .map(|p| Ok(p?.1)) // map the polls to the poll id and poll
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize; // cap the page size so the query stays within gas limits
+    let start = start_after.as_deref().map(Bound::exclusive); // start strictly after the given poll id
+
+    let polls = polls() // load the polls from the contract's state
+        .range(deps.storage, start, None, Order::Ascending) // iterate through the polls and order them in ascending order
+        .take(limit) // only take one page
+        .map(|p| {
+            let (poll_id, poll) = p?;
+            Ok(poll_info(&env, poll_id, poll)) // map the polls to the poll id, poll and status
+        })
         .collect::<StdResult<Vec<_>>>()?; // collect the polls into a vector and check for errors
 
     to_binary(&AllPollsResponse { polls }) // return the polls as binary data
 }
 
+// define the query_polls_by_creator function which is called by the query function and returns a page of a specific creator's polls
+fn query_polls_by_creator( // define required inputs to the query_polls_by_creator function
+    deps: Deps, // immutable contract state because we are just reading data, not writing data
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    creator: String, // creator's address as a String
+    start_after: Option<String>, // poll id to start the page after, None starts from the creator's first poll
+    limit: Option<u32>) // maximum number of polls to return
+    -> StdResult<Binary> { // define return result of the query_polls_by_creator function as binary data
+    let creator = deps.api.addr_validate(&creator)?; // validate the creator address and check for errors
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize; // cap the page size so the query stays within gas limits
+    let start = start_after.map(Bound::exclusive); // start strictly after the given poll id
+
+    let polls = polls()
+        .idx
+        .creator // use the creator index to only visit this creator's polls
+        .prefix(creator)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|p| {
+            let (poll_id, poll) = p?;
+            Ok(poll_info(&env, poll_id, poll))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&AllPollsResponse { polls }) // return the polls as binary data
+}
+
+// define the poll_info function which pairs a poll with its id and current status for the listing queries
+fn poll_info(env: &Env, poll_id: String, poll: Poll) -> PollInfo {
+    let status = poll.status(&env.block);
+    PollInfo {
+        poll_id,
+        poll,
+        status,
+    }
+}
+
 // define the query_poll function which is called by the query function and returns a specific poll in the contract's state
 fn query_poll( // define required inputs to the query_poll function
     deps: Deps, // immutable contract state because we are just reading data, not writing data
-    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
     poll_id: String) // poll id as a String
     -> StdResult<Binary> { // define return result of the query_poll function as binary data
-    let poll = POLLS.may_load(deps.storage, &poll_id)?; // load the poll from the contract's state if the poll id exists and check for errors
-    to_binary(&PollResponse { poll }) // return the poll as binary data
+    let poll = polls().may_load(deps.storage, &poll_id)?; // load the poll from the contract's state if the poll id exists and check for errors
+    let status = poll.as_ref().map(|poll| poll.status(&env.block)); // work out whether the poll is upcoming, open or closed at the current block
+    to_binary(&PollResponse { poll, status }) // return the poll and its status as binary data
 }
 
 // define the query_vote function which is called by the query function and returns a specific address' vote of a specific poll in the contract's state
@@ -205,53 +857,355 @@ fn query_vote( // define required inputs to the query_vote function
     poll_id: String) // specifc poll id as a String to be queried against the user's address
     -> StdResult<Binary> { // define return result of the query_vote function as binary data
     let validated_address = deps.api.addr_validate(&address).unwrap(); // validate the input address and unwrap the result
-    let vote = BALLOTS.may_load(deps.storage, (validated_address, &poll_id))?; // load the ballot from the contract's state if the address is valid and poll id exists and check for errors
+    let vote = ballots().may_load(deps.storage, (validated_address, &poll_id))?; // load the ballot from the contract's state if the address is valid and poll id exists and check for errors
 
     to_binary(&VoteResponse { vote }) // return the vote as binary data
 }
 
-// define tests module
-#[cfg(test)]
-mod tests {
+// define the query_ballots_by_voter function which is called by the query function and returns a page of every ballot a specific address has cast
+fn query_ballots_by_voter( // define required inputs to the query_ballots_by_voter function
+    deps: Deps, // immutable contract state because we are just reading data, not writing data
+    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    voter: String, // voter's address as a String
+    start_after: Option<String>, // poll id to start the page after, None starts from the voter's first ballot
+    limit: Option<u32>) // maximum number of ballots to return
+    -> StdResult<Binary> { // define return result of the query_ballots_by_voter function as binary data
+    let voter = deps.api.addr_validate(&voter)?; // validate the voter address and check for errors
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize; // cap the page size so the query stays within gas limits
+    let start = start_after.as_deref().map(Bound::exclusive); // start strictly after the given poll id
 
-    // import dependencies from contract.rs file
-    use crate::contract::{execute, instantiate, query};
+    // ballots are keyed by voter first, so a prefix on the voter visits only their ballots
+    let ballots = ballots()
+        .prefix(voter)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|b| Ok(b?.1))
+        .collect::<StdResult<Vec<_>>>()?;
 
-    // import dependencies from msg.rs file
-    use crate::msg::{
-        AllPollsResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, VoteResponse,
-    };
+    to_binary(&BallotsResponse { ballots }) // return the ballots as binary data
+}
 
-    // import dependencies from the cosmwasm_std library
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, from_binary};
+// define the query_results function which is called by the query function and returns the counted results of a specific poll
+fn query_results( // define required inputs to the query_results function
+    deps: Deps, // immutable contract state because we are just reading data, not writing data
+    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    poll_id: String) // poll id as a String
+    -> StdResult<Binary> { // define return result of the query_results function as binary data
+    let poll = polls().load(deps.storage, &poll_id)?; // load the poll from the contract's state and error if it does not exist
 
-    // create two mock addresses to use during testing
-    pub const ADDR1: &str = "addr1";
-    pub const ADDR2: &str = "addr2";
+    let results = match poll.poll_type {
+        // single-choice and approval polls are already fully counted in Poll.options
+        PollType::SingleChoice | PollType::Approval { .. } => {
+            let top = poll.options.iter().map(|option| option.votes).max().unwrap_or_default();
+            let mut leaders = poll.options.iter().filter(|option| option.votes == top);
+            // there is a winner only if a single option has the most votes
+            let winner = match (leaders.next(), leaders.next()) {
+                (Some(leader), None) if !top.is_zero() => Some(leader.id),
+                _ => None,
+            };
+            ResultsResponse {
+                rounds: vec![RoundResponse {
+                    tallies: poll.options,
+                    eliminated: vec![],
+                }],
+                winner,
+            }
+        }
+        // ranked-choice polls need every ballot to run the instant-runoff
+        PollType::RankedChoice => {
+            let ballots = ballots()
+                .idx
+                .poll
+                .prefix(poll_id)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| Ok(item?.1))
+                .collect::<StdResult<Vec<_>>>()?;
+            instant_runoff(&poll, &ballots)
+        }
+    };
 
-    #[test]
-    fn test_instantiate() {
-        // Mock the dependencies, must be mutable so we can pass it as a mutable, empty vector means our contract has no balance
-        let mut deps = mock_dependencies();
-        // Mock the contract environment, contains the block info, contract address, etc.
-        let env = mock_env();
-        // Mock the message info, ADDR1 will be the sender, the empty vec means we sent no funds.
-        let info = mock_info(ADDR1, &[]);
+    to_binary(&results) // return the results as binary data
+}
 
-        // Create a message where we (the sender) will be an admin
-        let msg = InstantiateMsg { admin: None };
-        // Call instantiate, unwrap to assert success
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+// define the query_commit_count function which is called by the query function and returns how many votes of a commit-reveal poll were committed and revealed
+fn query_commit_count( // define required inputs to the query_commit_count function
+    deps: Deps, // immutable contract state because we are just reading data, not writing data
+    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    poll_id: String) // poll id as a String
+    -> StdResult<Binary> { // define return result of the query_commit_count function as binary data
+    let poll = polls().load(deps.storage, &poll_id)?; // load the poll from the contract's state and error if it does not exist
+    let commit_reveal = poll
+        .commit_reveal
+        .ok_or_else(|| StdError::generic_err("Poll is not a commit-reveal poll"))?;
+    to_binary(&CommitCountResponse {
+        commits: commit_reveal.commits,
+        reveals: commit_reveal.reveals,
+    })
+}
 
-        // Assert admin is ADDR1
-        assert_eq!(
-            res.attributes,
-            vec![attr("action", "instantiate"), attr("admin", ADDR1)]
-        )
+// define the query_unrevealed_commitments function which is called by the query function and returns a page of the commitments never revealed
+fn query_unrevealed_commitments( // define required inputs to the query_unrevealed_commitments function
+    deps: Deps, // immutable contract state because we are just reading data, not writing data
+    env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    poll_id: String, // poll id as a String
+    start_after: Option<String>, // voter address to start the page after, None starts from the first voter
+    limit: Option<u32>) // maximum number of commitments to return
+    -> StdResult<Binary> { // define return result of the query_unrevealed_commitments function as binary data
+    let poll = polls().load(deps.storage, &poll_id)?; // load the poll from the contract's state and error if it does not exist
+    if poll.commit_reveal.is_none() {
+        return Err(StdError::generic_err("Poll is not a commit-reveal poll"));
     }
-    
-    #[test]
+    // until the reveal deadline passes a commitment may still be revealed, so it is not unrevealed yet
+    if poll.status(&env.block) != PollStatus::Closed {
+        return Err(StdError::generic_err("Reveal phase has not ended"));
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize; // cap the page size so the query stays within gas limits
+    let start_after = start_after.map(|voter| deps.api.addr_validate(&voter)).transpose()?; // validate the start address and check for errors
+    let start = start_after.as_ref().map(Bound::exclusive); // start strictly after the given voter
+
+    let commitments = COMMITMENTS
+        .prefix(&poll_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (voter, commitment) = item?;
+            Ok(CommitmentInfo { voter, commitment })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&CommitmentsResponse { commitments }) // return the commitments as binary data
+}
+
+// define the instant_runoff function which counts ranked-choice ballots round by round
+// each round every ballot counts toward its highest ranked option still in the running, an option with more than half
+// of the counted weight wins, otherwise the options with the lowest tally are eliminated and the next round is counted
+fn instant_runoff(poll: &Poll, ballots: &[Ballot]) -> ResultsResponse {
+    let mut remaining: Vec<&PollOption> = poll.options.iter().collect();
+    let mut rounds = vec![];
+    let mut winner = None;
+
+    while !remaining.is_empty() {
+        // tally every ballot toward its highest ranked remaining option, exhausted ballots are not counted
+        let mut tallies: Vec<PollOption> = remaining
+            .iter()
+            .map(|option| PollOption {
+                votes: Uint128::zero(),
+                ..(*option).clone()
+            })
+            .collect();
+        for ballot in ballots {
+            let choice = ballot
+                .options
+                .iter()
+                .find_map(|id| tallies.iter().position(|tally| &tally.id == id));
+            if let Some(i) = choice {
+                tallies[i].votes += ballot.weight;
+            }
+        }
+        let total: Uint128 = tallies.iter().map(|tally| tally.votes).sum();
+
+        // stop with a winner once an option holds a majority of the counted weight
+        if let Some(leader) = tallies.iter().find(|tally| tally.votes + tally.votes > total) {
+            winner = Some(leader.id);
+            rounds.push(RoundResponse {
+                tallies,
+                eliminated: vec![],
+            });
+            break;
+        }
+
+        // otherwise eliminate every option tied for the lowest tally
+        let lowest = tallies.iter().map(|tally| tally.votes).min().unwrap_or_default();
+        let eliminated: Vec<u32> = tallies
+            .iter()
+            .filter(|tally| tally.votes == lowest)
+            .map(|tally| tally.id)
+            .collect();
+        remaining.retain(|option| !eliminated.contains(&option.id));
+        rounds.push(RoundResponse {
+            tallies,
+            eliminated,
+        });
+    }
+
+    ResultsResponse { rounds, winner }
+}
+
+// define migrate function as the contract's 4th entrypoint, used to upgrade a deployed contract to this code
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate( // define required inputs to the migrate function
+    deps: DepsMut, // mutable contract state
+    _env: Env, // internal enviroment info such as block height, time, chain id, contract address, message sender, etc..
+    _msg: MigrateMsg, // blank migrate message
+) -> Result<Response, ContractError> { // define the result response of the migrate function and any errors that may occur
+    let stored = get_contract_version(deps.storage)?; // load the name and version of the code being replaced
+    if stored.contract != CONTRACT_NAME { // only a cw-starter contract can be migrated to cw-starter
+        return Err(ContractError::CannotMigrate {
+            previous_contract: stored.contract,
+        });
+    }
+    let version: Version = CONTRACT_VERSION.parse()?;
+    let stored_version: Version = stored.version.parse()?;
+    if stored_version > version { // downgrades could leave state this code cannot read
+        return Err(ContractError::CannotMigrateVersion {
+            previous_version: stored.version,
+        });
+    }
+
+    // v0.1.0 stored the original config, polls and ballots layout, convert it in place
+    if stored_version < Version::new(0, 2, 0) {
+        migrate_from_v0_1(deps.storage)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?; // record the new version
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+// define the migrate_from_v0_1 function which rewrites the v0.1.0 config, polls and ballots into the current layout
+// v0.1.0 polls were single-choice with no voting window or weight source, so every ballot counted as 1
+fn migrate_from_v0_1(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy_config = LEGACY_CONFIG.load(storage)?;
+    CONFIG.save(storage, &Config {
+        admin: legacy_config.admin,
+        creation_fee: None, // v0.1.0 polls were free to create
+        max_options: DEFAULT_MAX_OPTIONS, // v0.1.0 allowed 10 options
+    })?;
+    TREASURY.save(storage, &Uint128::zero())?;
+
+    // read every legacy record before writing, the new records are saved under the same keys
+    let legacy_polls = LEGACY_POLLS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let legacy_ballots = LEGACY_BALLOTS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (poll_id, legacy) in legacy_polls {
+        let poll = Poll {
+            creator: legacy.creator,
+            question: legacy.question,
+            options: legacy
+                .options
+                .into_iter()
+                .enumerate()
+                .map(|(id, (text, votes))| PollOption {
+                    id: id as u32,
+                    text,
+                    votes: Uint128::from(votes),
+                })
+                .collect(),
+            start: None,
+            end: None,
+            weight_source: None,
+            poll_type: PollType::SingleChoice,
+            commit_reveal: None,
+        };
+        // remove the legacy record first, the IndexedMap would otherwise try to read it as the old value of a Poll
+        LEGACY_POLLS.remove(storage, &poll_id);
+        polls().save(storage, &poll_id, &poll)?;
+    }
+
+    for ((voter, poll_id), legacy) in legacy_ballots {
+        LEGACY_BALLOTS.remove(storage, (voter.clone(), &poll_id));
+        // look up the id of the voted option, ballots of polls or options that no longer exist were never counted so they are dropped
+        let option = polls()
+            .may_load(storage, &poll_id)?
+            .and_then(|poll| poll.options.into_iter().find(|option| option.text == legacy.option));
+        if let Some(option) = option {
+            let ballot = Ballot {
+                poll_id: poll_id.clone(),
+                options: vec![option.id],
+                weight: Uint128::new(1),
+            };
+            ballots().save(storage, (voter, &poll_id), &ballot)?;
+        }
+    }
+    Ok(())
+}
+
+// define tests module
+#[cfg(test)]
+mod tests {
+
+    // import dependencies from contract.rs file
+    use crate::contract::{execute, instantiate, migrate, query};
+
+    // import the commitment helper from helpers.rs file
+    use crate::helpers::vote_commitment;
+
+    // import dependencies from msg.rs file
+    use crate::msg::{
+        AllPollsResponse, BallotsResponse, CommitCountResponse, CommitmentsResponse, CreatePollMsg,
+        Cw20SnapshotQueryMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OptionRef, PollResponse,
+        QueryMsg, ReceiveMsg, ResultsResponse, RoundResponse, VoteResponse, WeightSourceMsg,
+    };
+
+    // import dependencies from state.rs and error.rs files
+    use crate::state::{
+        LegacyBallot, LegacyConfig, LegacyPoll, PollFee, PollOption, PollStatus, PollType,
+        LEGACY_BALLOTS, LEGACY_CONFIG, LEGACY_POLLS,
+    };
+    use crate::ContractError;
+
+    // import dependencies from the cosmwasm_std and cw_utils libraries
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        attr, coins, from_binary, from_slice, to_binary, Addr, BankMsg, ContractResult, CosmosMsg,
+        SystemResult, Uint128, WasmMsg, WasmQuery,
+    };
+    use cw2::{get_contract_version, set_contract_version};
+    use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, Denom};
+    use cw4::{Cw4QueryMsg, MemberResponse};
+    use cw_utils::{Expiration, PaymentError};
+
+    // create two mock addresses to use during testing
+    pub const ADDR1: &str = "addr1";
+    pub const ADDR2: &str = "addr2";
+    pub const ADMIN: &str = "admin";
+
+    // create mock cw4 group and cw20 token addresses to use as weight sources during testing
+    pub const GROUP: &str = "group";
+    pub const TOKEN: &str = "token";
+
+    // build the expected PollOption of a poll or results round
+    fn option(id: u32, text: &str, votes: u128) -> PollOption {
+        PollOption {
+            id,
+            text: text.to_string(),
+            votes: Uint128::new(votes),
+        }
+    }
+
+    #[test]
+    fn test_instantiate() {
+        // Mock the dependencies, must be mutable so we can pass it as a mutable, empty vector means our contract has no balance
+        let mut deps = mock_dependencies();
+        // Mock the contract environment, contains the block info, contract address, etc.
+        let env = mock_env();
+        // Mock the message info, ADDR1 will be the sender, the empty vec means we sent no funds.
+        let info = mock_info(ADDR1, &[]);
+
+        // Create a message where we (the sender) will be an admin
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        // Call instantiate, unwrap to assert success
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        // Assert admin is ADDR1
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "instantiate"), attr("admin", ADDR1)]
+        )
+    }
+
+    #[test]
     // Test instantiate with admin
     fn test_instantiate_with_admin() {
         // mock dependencies using mock_dependencies from cosmwasm_std
@@ -266,142 +1220,1487 @@ mod tests {
         let msg = InstantiateMsg {
             // set admin to ADDR2 using Some method which is an enum that can be either something or nothing
             admin: Some(ADDR2.to_string()), // Some is an Option enum
+            creation_fee: None,
+            max_options: None,
+        };
+        // Unwrap to assert success
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        // Assert admin is ADDR2 instead
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "instantiate"), attr("admin", ADDR2),]
+        );
+    }
+
+    #[test]
+    fn test_execute_create_poll_valid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // New execute msg
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            ..Default::default()
+        });
+
+        // Unwrap to assert success
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_execute_create_poll_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite number?".to_string(),
+            options: vec![
+                "1".to_string(),
+                "2".to_string(),
+                "3".to_string(),
+                "4".to_string(),
+                "5".to_string(),
+                "6".to_string(),
+                "7".to_string(),
+                "8".to_string(),
+                "9".to_string(),
+                "10".to_string(),
+                "11".to_string(),
+            ],
+            ..Default::default()
+        });
+
+        // Unwrap error to assert failure
+        let _err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    }
+
+    #[test]
+    fn test_execute_create_poll_existing_id() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR1 creates a poll and votes in it
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR2 tries to create a poll with the same id
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite number?".to_string(),
+            options: vec!["1".to_string(), "2".to_string()],
+            ..Default::default()
+        });
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(
+            matches!(err, ContractError::PollAlreadyExists { poll_id } if poll_id == "some_id")
+        );
+
+        // ADDR1 can still change their vote in the original poll
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Cosmos Hub".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        let poll = res.poll.unwrap();
+        assert_eq!(poll.question, "What's your favourite Cosmos coin?");
+        assert_eq!(
+            poll.options,
+            vec![option(0, "Cosmos Hub", 1), option(1, "Juno", 0)]
+        );
+    }
+
+    #[test]
+    fn test_execute_vote_valid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the vote, first time voting
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Change the vote
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Osmosis".into(),
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_execute_vote_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the vote, some_id poll is not created yet.
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+        // Unwrap to assert error
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollNotFound { .. }));
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Vote on a now existing poll but the option "DVPN" does not exist
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "DVPN".into(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidOption { .. }));
+
+        // Option ids past the last option do not exist either
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: 3.into(),
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidOption { .. }));
+    }
+
+    #[test]
+    fn test_execute_vote_by_option_id() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // A vote can name the option by its id instead of its text
+        let msg: ExecuteMsg = from_slice(br#"{"vote":{"poll_id":"some_id","vote":2}}"#).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg: ExecuteMsg =
+            from_slice(br#"{"vote":{"poll_id":"some_id","vote":"Juno"}}"#).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // Expect both ballots to be recorded by option id
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.poll.unwrap().options,
+            vec![
+                option(0, "Cosmos Hub", 0),
+                option(1, "Juno", 1),
+                option(2, "Osmosis", 1)
+            ]
+        );
+        let msg = QueryMsg::Vote {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.vote.unwrap().options, vec![2]);
+    }
+
+    #[test]
+    fn test_execute_create_poll_invalid_options() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract allowing at most 3 options per poll
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: Some(3),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let poll = |question: &str, options: &[&str]| {
+            ExecuteMsg::CreatePoll(CreatePollMsg {
+                poll_id: "some_id".to_string(),
+                question: question.to_string(),
+                options: options.iter().map(|option| option.to_string()).collect(),
+                ..Default::default()
+            })
+        };
+
+        // More options than the configured limit
+        let msg = poll("Pick a coin", &["Cosmos Hub", "Juno", "Osmosis", "DVPN"]);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyOptions {}));
+
+        // The same option twice
+        let msg = poll("Pick a coin", &["Juno", "Osmosis", "Juno"]);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateOption { option } if option == "Juno"));
+
+        // The same option with different case or padding
+        let msg = poll("Should we?", &["Yes", " yes"]);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateOption { option } if option == "yes"));
+
+        // A blank option
+        let msg = poll("Pick a coin", &["Juno", " "]);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::EmptyOption {}));
+
+        // A blank or oversize question
+        let msg = poll("", &["Juno", "Osmosis"]);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::EmptyQuestion {}));
+        let msg = poll(&"?".repeat(257), &["Juno", "Osmosis"]);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::QuestionTooLong { max: 256 }));
+
+        // The limit counts characters, so 256 multi-byte characters still fit
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "long_id".to_string(),
+            question: "é".repeat(256),
+            options: vec!["Juno".to_string(), "Osmosis".to_string()],
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Expect a valid poll to be created with trimmed options numbered from 0
+        let msg = poll("Pick a coin", &["Cosmos Hub", " Juno", "Osmosis "]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.poll.unwrap().options,
+            vec![
+                option(0, "Cosmos Hub", 0),
+                option(1, "Juno", 0),
+                option(2, "Osmosis", 0)
+            ]
+        );
+
+        // Edits are validated the same way
+        let msg = ExecuteMsg::EditPoll {
+            poll_id: "some_id".to_string(),
+            question: None,
+            options: Some(vec!["Juno".to_string(), "Juno".to_string()]),
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateOption { .. }));
+    }
+
+    #[test]
+    fn test_execute_create_poll_invalid_window() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll that has already ended
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            end: Some(Expiration::AtHeight(env.block.height)),
+            ..Default::default()
+        });
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidVotingWindow {}));
+
+        // Create a poll that closes before it opens
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            start: Some(Expiration::AtHeight(env.block.height + 10)),
+            end: Some(Expiration::AtHeight(env.block.height + 5)),
+            ..Default::default()
+        });
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidVotingWindow {}));
+    }
+
+    #[test]
+    fn test_execute_vote_window() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll that opens in 10 blocks and closes in 20 blocks
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            start: Some(Expiration::AtHeight(env.block.height + 10)),
+            end: Some(Expiration::AtHeight(env.block.height + 20)),
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+
+        // Voting before the poll opens fails
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::PollNotStarted {}));
+
+        // Voting inside the window succeeds
+        env.block.height += 10;
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+        // Voting after the poll closes fails
+        env.block.height += 10;
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed {}));
+    }
+
+    #[test]
+    fn test_query_poll_status() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll that opens in 100 seconds and closes in 200 seconds
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            start: Some(Expiration::AtTime(env.block.time.plus_seconds(100))),
+            end: Some(Expiration::AtTime(env.block.time.plus_seconds(200))),
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+
+        // Expect the poll to be upcoming
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, Some(PollStatus::Upcoming));
+
+        // Expect the poll to be open
+        env.block.time = env.block.time.plus_seconds(100);
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, Some(PollStatus::Open));
+
+        // Expect the poll to be closed
+        env.block.time = env.block.time.plus_seconds(100);
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, Some(PollStatus::Closed));
+    }
+
+    #[test]
+    fn test_execute_vote_weighted_cw20() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        // Mock the snapshot token contract, ADDR1 holds 100 tokens up to the poll's creation height, then sends them to ADDR2
+        let creation_height = env.block.height;
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == TOKEN => {
+                let balance = match from_slice(msg).unwrap() {
+                    Cw20SnapshotQueryMsg::BalanceAtHeight { address, height } => {
+                        match (address.as_str(), height <= creation_height) {
+                            (ADDR1, true) | (ADDR2, false) => Uint128::new(100),
+                            _ => Uint128::zero(),
+                        }
+                    }
+                };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&BalanceResponse { balance }).unwrap(),
+                ))
+            }
+            _ => panic!("unexpected query"),
+        });
+
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll weighted by token balance
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            weight_source: Some(WeightSourceMsg::Cw20Balance {
+                addr: TOKEN.to_string(),
+            }),
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Vote, then change the vote
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Cosmos Hub".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Expect the full weight to have moved to the new option
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.poll.unwrap().options,
+            vec![option(0, "Cosmos Hub", 100), option(1, "Juno", 0)]
+        );
+
+        // Move to a later block, where ADDR1's tokens now belong to ADDR2
+        env.block.height += 1;
+
+        // Expect ADDR2 to be rejected as they held no tokens when the poll was created
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoVotingPower {}));
+
+        // Expect the moved tokens not to have been counted twice
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.poll.unwrap().options,
+            vec![option(0, "Cosmos Hub", 100), option(1, "Juno", 0)]
+        );
+    }
+
+    #[test]
+    fn test_execute_create_poll_cw20_without_snapshots() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        // Mock a plain cw20 token contract, which only answers the spec queries
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == TOKEN => {
+                match from_slice::<Cw20QueryMsg>(msg) {
+                    Ok(Cw20QueryMsg::Balance { .. }) => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&BalanceResponse {
+                            balance: Uint128::new(100),
+                        })
+                        .unwrap(),
+                    )),
+                    _ => SystemResult::Ok(ContractResult::Err("unknown variant".to_string())),
+                }
+            }
+            _ => panic!("unexpected query"),
+        });
+
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Expect a poll weighted by the token to be refused, as its balances could be moved and vote twice
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            weight_source: Some(WeightSourceMsg::Cw20Balance {
+                addr: TOKEN.to_string(),
+            }),
+            ..Default::default()
+        });
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Cw20NoSnapshot { addr } if addr == TOKEN));
+    }
+
+    #[test]
+    fn test_execute_vote_weighted_cw4() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        // Mock the group contract, ADDR1 has weight 7 at the poll's creation height and ADDR2 is not a member
+        let creation_height = env.block.height;
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == GROUP => {
+                let weight = match from_slice(msg).unwrap() {
+                    Cw4QueryMsg::Member { addr, at_height }
+                        if addr == ADDR1 && at_height == Some(creation_height) =>
+                    {
+                        Some(7)
+                    }
+                    _ => None,
+                };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&MemberResponse { weight }).unwrap(),
+                ))
+            }
+            _ => panic!("unexpected query"),
+        });
+
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll weighted by group membership
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            weight_source: Some(WeightSourceMsg::Cw4Group {
+                addr: GROUP.to_string(),
+            }),
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Vote a few blocks later, the weight still comes from the creation height
+        let mut later = env.clone();
+        later.block.height += 5;
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+        let _res = execute(deps.as_mut(), later.clone(), info, msg.clone()).unwrap();
+
+        // Expect the ballot to record the group weight
+        let msg_query = QueryMsg::Vote {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), later.clone(), msg_query).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.vote.unwrap().weight, Uint128::new(7));
+
+        // Expect non-members to be rejected
+        let err = execute(deps.as_mut(), later, mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoVotingPower {}));
+    }
+
+    #[test]
+    fn test_execute_cast_ballot_approval() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create an approval poll where voters may pick up to 2 options
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "Which Cosmos coins do you hold?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            poll_type: PollType::Approval { max_choices: 2 },
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Picking 3 options is too many
+        let msg = ExecuteMsg::CastBallot {
+            poll_id: "some_id".to_string(),
+            choices: vec!["Cosmos Hub".into(), "Juno".into(), "Osmosis".into()],
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyChoices { max: 2 }));
+
+        // Picking the same option twice is rejected
+        let msg = ExecuteMsg::CastBallot {
+            poll_id: "some_id".to_string(),
+            choices: vec!["Juno".into(), "Juno".into()],
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateChoice { .. }));
+
+        // ADDR1 approves two options, ADDR2 approves one
+        let msg = ExecuteMsg::CastBallot {
+            poll_id: "some_id".to_string(),
+            choices: vec!["Juno".into(), "Osmosis".into()],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // Expect every approved option to be counted and Juno to win
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.rounds[0].tallies,
+            vec![
+                option(0, "Cosmos Hub", 0),
+                option(1, "Juno", 2),
+                option(2, "Osmosis", 1),
+            ]
+        );
+        assert_eq!(res.winner, Some(1));
+    }
+
+    #[test]
+    fn test_execute_cast_ballot_single_choice() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a single-choice poll
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Expect a ballot with two choices to be rejected
+        let msg = ExecuteMsg::CastBallot {
+            poll_id: "some_id".to_string(),
+            choices: vec!["Cosmos Hub".into(), "Juno".into()],
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyChoices { max: 1 }));
+
+        // Expect an empty ballot to be rejected
+        let msg = ExecuteMsg::CastBallot {
+            poll_id: "some_id".to_string(),
+            choices: vec![],
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoChoices {}));
+    }
+
+    #[test]
+    fn test_query_results_ranked_choice() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a ranked-choice poll
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "Rank your favourite Cosmos coins".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            poll_type: PollType::RankedChoice,
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Five voters rank their preferences, nobody has a first-preference majority
+        let ballots = [
+            ("voter1", vec!["Cosmos Hub", "Juno"]),
+            ("voter2", vec!["Cosmos Hub", "Juno"]),
+            ("voter3", vec!["Juno", "Cosmos Hub"]),
+            ("voter4", vec!["Juno", "Cosmos Hub"]),
+            ("voter5", vec!["Osmosis", "Juno"]),
+        ];
+        for (voter, choices) in ballots {
+            let msg = ExecuteMsg::CastBallot {
+                poll_id: "some_id".to_string(),
+                choices: choices.into_iter().map(OptionRef::from).collect(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        // Expect Osmosis to be eliminated first and its voter's second preference to decide for Juno
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.rounds,
+            vec![
+                RoundResponse {
+                    tallies: vec![
+                        option(0, "Cosmos Hub", 2),
+                        option(1, "Juno", 2),
+                        option(2, "Osmosis", 1),
+                    ],
+                    eliminated: vec![2],
+                },
+                RoundResponse {
+                    tallies: vec![option(0, "Cosmos Hub", 2), option(1, "Juno", 3),],
+                    eliminated: vec![],
+                },
+            ]
+        );
+        assert_eq!(res.winner, Some(1));
+    }
+
+    #[test]
+    fn test_commit_reveal_poll() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a commit-reveal poll taking commitments for 10 blocks and reveals for 10 more
+        let height = env.block.height;
+        let poll = CreatePollMsg {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            end: Some(Expiration::AtHeight(height + 10)),
+            reveal_end: Some(Expiration::AtHeight(height + 20)),
+            ..Default::default()
+        };
+        let msg = ExecuteMsg::CreatePoll(poll.clone());
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a second poll with the same options and windows
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "other_id".to_string(),
+            ..poll
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Plain votes and malformed commitments are rejected
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Juno".into(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::CommitRevealPoll {}));
+        let msg = ExecuteMsg::CommitVote {
+            poll_id: "some_id".to_string(),
+            commitment: vec![1, 2, 3].into(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCommitment {}));
+
+        // Three voters commit, ADDR1 commits twice
+        let commits = [
+            (ADDR1, 1, "salt1"),
+            (ADDR1, 1, "salt1"),
+            (ADDR2, 2, "salt2"),
+            (ADMIN, 1, "salt3"),
+        ];
+        for (voter, option, salt) in commits {
+            let msg = ExecuteMsg::CommitVote {
+                poll_id: "some_id".to_string(),
+                commitment: vote_commitment(&Addr::unchecked(voter), "some_id", option, salt),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        // ADDR1 replays their commitment in the second poll
+        let msg = ExecuteMsg::CommitVote {
+            poll_id: "other_id".to_string(),
+            commitment: vote_commitment(&Addr::unchecked(ADDR1), "some_id", 1, "salt1"),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Expect nothing to be tallied or readable while the poll takes commitments
+        let msg = QueryMsg::Vote {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert!(res.vote.is_none());
+        let reveal = |vote: OptionRef, salt: &str| ExecuteMsg::RevealVote {
+            poll_id: "some_id".to_string(),
+            vote,
+            salt: salt.to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            reveal("Juno".into(), "salt1"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RevealNotStarted {}));
+
+        // A poll with commitments can no longer be edited
+        let msg = ExecuteMsg::EditPoll {
+            poll_id: "some_id".to_string(),
+            question: Some("What's your least favourite Cosmos coin?".to_string()),
+            options: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollHasVotes {}));
+
+        // Move into the reveal phase, commitments are no longer taken
+        env.block.height = height + 10;
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, Some(PollStatus::Revealing));
+        let msg = ExecuteMsg::CommitVote {
+            poll_id: "some_id".to_string(),
+            commitment: vote_commitment(&Addr::unchecked(ADDR2), "some_id", 0, "salt"),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed {}));
+
+        // A reveal must match the commitment and can only be made once
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            reveal("Juno".into(), "salt2"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CommitmentMismatch {}));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            reveal("Osmosis".into(), "salt1"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CommitmentMismatch {}));
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            reveal("Juno".into(), "salt1"),
+        )
+        .unwrap();
+
+        // The replayed commitment cannot be revealed in the second poll, as it was built for the first
+        let msg = ExecuteMsg::RevealVote {
+            poll_id: "other_id".to_string(),
+            vote: "Juno".into(),
+            salt: "salt1".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::CommitmentMismatch {}));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            reveal("Juno".into(), "salt1"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoCommitment {}));
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            reveal(2.into(), "salt2"),
+        )
+        .unwrap();
+
+        // Expect unrevealed commitments to stay hidden until the reveal deadline
+        let unrevealed = QueryMsg::UnrevealedCommitments {
+            poll_id: "some_id".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let _err = query(deps.as_ref(), env.clone(), unrevealed.clone()).unwrap_err();
+        let msg = QueryMsg::CommitCount {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: CommitCountResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res,
+            CommitCountResponse {
+                commits: 3,
+                reveals: 2
+            }
+        );
+
+        // After the reveal deadline ADMIN's commitment is listed and can no longer be revealed
+        env.block.height = height + 20;
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            reveal("Juno".into(), "salt3"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed {}));
+        let bin = query(deps.as_ref(), env.clone(), unrevealed).unwrap();
+        let res: CommitmentsResponse = from_binary(&bin).unwrap();
+        let voters: Vec<_> = res.commitments.iter().map(|c| c.voter.as_str()).collect();
+        assert_eq!(voters, vec![ADMIN]);
+
+        // Expect only the revealed votes to be tallied
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
         };
-        // Unwrap to assert success
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-        // Assert admin is ADDR2 instead
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
         assert_eq!(
-            res.attributes,
-            vec![attr("action", "instantiate"), attr("admin", ADDR2),]
+            res.rounds[0].tallies,
+            vec![
+                option(0, "Cosmos Hub", 0),
+                option(1, "Juno", 1),
+                option(2, "Osmosis", 1)
+            ]
         );
+        assert_eq!(res.winner, None);
     }
 
     #[test]
-    fn test_execute_create_poll_valid() {
+    fn test_execute_create_commit_reveal_poll_invalid() {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // New execute msg
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id".to_string(),
-            question: "What's your favourite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
+        let height = env.block.height;
+        let poll = |end: Option<u64>, reveal_end: u64, poll_type: PollType| {
+            ExecuteMsg::CreatePoll(CreatePollMsg {
+                poll_id: "some_id".to_string(),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                end: end.map(Expiration::AtHeight),
+                reveal_end: Some(Expiration::AtHeight(reveal_end)),
+                poll_type,
+                ..Default::default()
+            })
         };
 
-        // Unwrap to assert success
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        // The commit phase must end, and before the reveal phase does
+        let msg = poll(None, height + 20, PollType::SingleChoice);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidVotingWindow {}));
+        let msg = poll(Some(height + 20), height + 10, PollType::SingleChoice);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidVotingWindow {}));
+
+        // Only single-choice polls can be commit-reveal
+        let msg = poll(Some(height + 10), height + 20, PollType::RankedChoice);
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::CommitRevealSingleChoice {}));
     }
 
     #[test]
-    fn test_execute_create_poll_invalid() {
+    fn test_execute_close_poll() {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        // Instantiate the contract with ADMIN as admin
+        let msg = InstantiateMsg {
+            admin: Some(ADMIN.to_string()),
+            creation_fee: None,
+            max_options: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id".to_string(),
-            question: "What's your favourite number?".to_string(),
-            options: vec![
-                "1".to_string(),
-                "2".to_string(),
-                "3".to_string(),
-                "4".to_string(),
-                "5".to_string(),
-                "6".to_string(),
-                "7".to_string(),
-                "8".to_string(),
-                "9".to_string(),
-                "10".to_string(),
-                "11".to_string(),
-            ],
+        // ADDR1 creates two polls
+        for poll_id in ["poll_a", "poll_b"] {
+            let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+                poll_id: poll_id.to_string(),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                ..Default::default()
+            });
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // Closing a poll that does not exist fails
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "poll_z".to_string(),
         };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollNotFound { .. }));
 
-        // Unwrap error to assert failure
-        let _err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        // ADDR2 is neither the creator nor the admin
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "poll_a".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotCreatorOrAdmin {}));
+
+        // The creator closes poll_a and the admin closes poll_b
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "poll_a".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let msg_b = ExecuteMsg::ClosePoll {
+            poll_id: "poll_b".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg_b).unwrap();
+
+        // A closed poll cannot be closed again or voted in
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed {}));
+        let msg = ExecuteMsg::Vote {
+            poll_id: "poll_a".to_string(),
+            vote: "Juno".into(),
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed {}));
     }
 
     #[test]
-    fn test_execute_vote_valid() {
+    fn test_execute_delete_and_edit_poll() {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        // Instantiate the contract with ADMIN as admin
+        let msg = InstantiateMsg {
+            admin: Some(ADMIN.to_string()),
+            creation_fee: None,
+            max_options: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // Create the poll
-        let msg = ExecuteMsg::CreatePoll {
+        // Create a poll
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
             poll_id: "some_id".to_string(),
             question: "What's your favourite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
-        };
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            ..Default::default()
+        });
         let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // Create the vote, first time voting
-        let msg = ExecuteMsg::Vote {
+        // ADDR2 cannot edit it
+        let edit = ExecuteMsg::EditPoll {
             poll_id: "some_id".to_string(),
-            vote: "Juno".to_string(),
+            question: Some("What's your favourite Cosmos chain?".to_string()),
+            options: Some(vec!["Juno".to_string(), "Osmosis".to_string()]),
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            edit.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotCreatorOrAdmin {}));
 
-        // Change the vote
+        // The admin edits it before anybody votes
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            edit.clone(),
+        )
+        .unwrap();
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(poll.question, "What's your favourite Cosmos chain?");
+        assert_eq!(
+            poll.options,
+            vec![option(0, "Juno", 0), option(1, "Osmosis", 0)]
+        );
+
+        // Once somebody votes the poll can no longer be edited or deleted
         let msg = ExecuteMsg::Vote {
             poll_id: "some_id".to_string(),
-            vote: "Osmosis".to_string(),
+            vote: "Osmosis".into(),
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), edit).unwrap_err();
+        assert!(matches!(err, ContractError::PollHasVotes {}));
+        let delete = ExecuteMsg::DeletePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), delete).unwrap_err();
+        assert!(matches!(err, ContractError::PollHasVotes {}));
+
+        // A poll without votes can be deleted by its creator
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+            poll_id: "other_id".to_string(),
+            question: "What's your colour?".to_string(),
+            options: vec!["Red".to_string(), "Green".to_string()],
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let delete = ExecuteMsg::DeletePoll {
+            poll_id: "other_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, delete).unwrap();
+        let msg = QueryMsg::Poll {
+            poll_id: "other_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert!(res.poll.is_none());
     }
 
     #[test]
-    fn test_execute_vote_invalid() {
+    fn test_execute_update_admin() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &[]);
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let info = mock_info(ADMIN, &[]);
+        // Instantiate the contract, ADMIN is the sender and so the admin
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // Create the vote, some_id poll is not created yet.
-        let msg = ExecuteMsg::Vote {
+        // ADDR1 creates a poll
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
             poll_id: "some_id".to_string(),
-            vote: "Juno".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            ..Default::default()
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+
+        // Only the admin can hand over the admin role
+        let msg = ExecuteMsg::UpdateAdmin {
+            admin: ADDR2.to_string(),
         };
-        // Unwrap to assert error
-        let _err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "update_admin"), attr("admin", ADDR2)]
+        );
 
-        // Create the poll
-        let msg = ExecuteMsg::CreatePoll {
+        // The old admin can no longer manage ADDR1's poll, the new admin can
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotCreatorOrAdmin {}));
+        let _res = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn test_execute_create_poll_native_fee() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        // Instantiate the contract with a creation fee of 100ujuno
+        let msg = InstantiateMsg {
+            admin: Some(ADMIN.to_string()),
+            creation_fee: Some(PollFee {
+                denom: Denom::Native("ujuno".to_string()),
+                amount: Uint128::new(100),
+            }),
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
             poll_id: "some_id".to_string(),
             question: "What's your favourite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            ..Default::default()
+        });
+
+        // Creating a poll without paying fails
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Payment(PaymentError::NoFunds {})
+        ));
+
+        // Paying the wrong amount fails
+        let info = mock_info(ADDR1, &coins(50, "ujuno"));
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFee { .. }));
+
+        // Paying the fee creates the poll
+        let info = mock_info(ADDR1, &coins(100, "ujuno"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Only the admin can withdraw the fees
+        let msg = ExecuteMsg::WithdrawFees {
+            recipient: ADDR2.to_string(),
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ADDR2.to_string(),
+                amount: coins(100, "ujuno"),
+            })
+        );
 
-        // Vote on a now existing poll but the option "DVPN" does not exist
-        let msg = ExecuteMsg::Vote {
+        // The treasury is now empty
+        let err = execute(deps.as_mut(), env, mock_info(ADMIN, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoFeesToWithdraw {}));
+    }
+
+    #[test]
+    fn test_execute_create_poll_cw20_fee() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        // Instantiate the contract with a creation fee of 100 TOKEN
+        let msg = InstantiateMsg {
+            admin: Some(ADMIN.to_string()),
+            creation_fee: Some(PollFee {
+                denom: Denom::Cw20(Addr::unchecked(TOKEN)),
+                amount: Uint128::new(100),
+            }),
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg).unwrap();
+
+        let create_poll_msg = CreatePollMsg {
             poll_id: "some_id".to_string(),
-            vote: "DVPN".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            ..Default::default()
         };
-        let _err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+
+        // The native CreatePoll cannot pay a cw20 fee
+        let msg = ExecuteMsg::CreatePoll(create_poll_msg.clone());
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFee { .. }));
+
+        // Tokens from another cw20 contract are rejected
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ADDR1.to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::CreatePoll(create_poll_msg.clone())).unwrap(),
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other_token", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFee { .. }));
+
+        // Sending the fee token creates the poll on behalf of the token sender
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(TOKEN, &[]), msg).unwrap();
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.poll.unwrap().creator, ADDR1);
+
+        // Withdrawing sends the tokens with a cw20 transfer
+        let msg = ExecuteMsg::WithdrawFees {
+            recipient: ADDR2.to_string(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info(ADMIN, &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: ADDR2.to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
     }
 
     #[test]
@@ -410,11 +2709,15 @@ mod tests {
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Create a poll
-        let msg = ExecuteMsg::CreatePoll {
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
             poll_id: "some_id_1".to_string(),
             question: "What's your favourite Cosmos coin?".to_string(),
             options: vec![
@@ -422,22 +2725,149 @@ mod tests {
                 "Juno".to_string(),
                 "Osmosis".to_string(),
             ],
-        };
+            ..Default::default()
+        });
         let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Create a second poll
-        let msg = ExecuteMsg::CreatePoll {
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
             poll_id: "some_id_2".to_string(),
             question: "What's your colour?".to_string(),
             options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
-        };
+            ..Default::default()
+        });
         let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // Query
-        let msg = QueryMsg::AllPolls {};
-        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
         let res: AllPollsResponse = from_binary(&bin).unwrap();
         assert_eq!(res.polls.len(), 2);
+        assert_eq!(res.polls[0].poll_id, "some_id_1");
+        assert_eq!(res.polls[0].status, PollStatus::Open);
+
+        // Query one poll at a time
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: Some(1),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].poll_id, "some_id_1");
+
+        // Query the next page
+        let msg = QueryMsg::AllPolls {
+            start_after: Some("some_id_1".to_string()),
+            limit: Some(1),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].poll_id, "some_id_2");
+    }
+
+    #[test]
+    fn test_query_polls_by_creator() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR1 creates two polls and ADDR2 creates one
+        for (creator, poll_id) in [(ADDR1, "poll_a"), (ADDR2, "poll_b"), (ADDR1, "poll_c")] {
+            let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+                poll_id: poll_id.to_string(),
+                question: "What's your colour?".to_string(),
+                options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+                ..Default::default()
+            });
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(creator, &[]), msg).unwrap();
+        }
+
+        // Expect only ADDR1's polls
+        let msg = QueryMsg::PollsByCreator {
+            creator: ADDR1.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["poll_a", "poll_c"]);
+
+        // Expect the page after poll_a to only hold poll_c
+        let msg = QueryMsg::PollsByCreator {
+            creator: ADDR1.to_string(),
+            start_after: Some("poll_a".to_string()),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["poll_c"]);
+    }
+
+    #[test]
+    fn test_query_ballots_by_voter() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create three polls and vote in two of them
+        for poll_id in ["poll_a", "poll_b", "poll_c"] {
+            let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
+                poll_id: poll_id.to_string(),
+                question: "What's your colour?".to_string(),
+                options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+                ..Default::default()
+            });
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        for poll_id in ["poll_a", "poll_c"] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: poll_id.to_string(),
+                vote: "Red".into(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // Expect both ballots to be listed
+        let msg = QueryMsg::BallotsByVoter {
+            voter: ADDR1.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: BallotsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.ballots.iter().map(|b| b.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["poll_a", "poll_c"]);
+
+        // Expect ADDR2 to have no ballots
+        let msg = QueryMsg::BallotsByVoter {
+            voter: ADDR2.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: BallotsResponse = from_binary(&bin).unwrap();
+        assert!(res.ballots.is_empty());
     }
 
     #[test]
@@ -446,11 +2876,15 @@ mod tests {
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Create a poll
-        let msg = ExecuteMsg::CreatePoll {
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
             poll_id: "some_id_1".to_string(),
             question: "What's your favourite Cosmos coin?".to_string(),
             options: vec![
@@ -458,7 +2892,8 @@ mod tests {
                 "Juno".to_string(),
                 "Osmosis".to_string(),
             ],
-        };
+            ..Default::default()
+        });
         let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // Query for the poll that exists
@@ -486,11 +2921,15 @@ mod tests {
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            creation_fee: None,
+            max_options: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Create a poll
-        let msg = ExecuteMsg::CreatePoll {
+        let msg = ExecuteMsg::CreatePoll(CreatePollMsg {
             poll_id: "some_id_1".to_string(),
             question: "What's your favourite Cosmos coin?".to_string(),
             options: vec![
@@ -498,13 +2937,14 @@ mod tests {
                 "Juno".to_string(),
                 "Osmosis".to_string(),
             ],
-        };
+            ..Default::default()
+        });
         let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Create a vote
         let msg = ExecuteMsg::Vote {
             poll_id: "some_id_1".to_string(),
-            vote: "Juno".to_string(),
+            vote: "Juno".into(),
         };
         let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -528,4 +2968,116 @@ mod tests {
         // Expect the vote to not exist
         assert!(res.vote.is_none());
     }
+
+    #[test]
+    fn test_migrate_from_v0_1() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // Store a poll and two ballots in the v0.1.0 layout
+        set_contract_version(deps.as_mut().storage, "crates.io:cw-starter", "0.1.0").unwrap();
+        let legacy_config = LegacyConfig {
+            admin: Addr::unchecked(ADMIN),
+        };
+        LEGACY_CONFIG
+            .save(deps.as_mut().storage, &legacy_config)
+            .unwrap();
+        let legacy_poll = LegacyPoll {
+            creator: Addr::unchecked(ADDR1),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                ("Cosmos Hub".to_string(), 0),
+                ("Juno".to_string(), 1),
+                ("Osmosis".to_string(), 1),
+            ],
+        };
+        LEGACY_POLLS
+            .save(deps.as_mut().storage, "some_id", &legacy_poll)
+            .unwrap();
+        for (voter, option) in [(ADDR1, "Juno"), (ADDR2, "Osmosis")] {
+            let ballot = LegacyBallot {
+                option: option.to_string(),
+            };
+            LEGACY_BALLOTS
+                .save(
+                    deps.as_mut().storage,
+                    (Addr::unchecked(voter), "some_id"),
+                    &ballot,
+                )
+                .unwrap();
+        }
+
+        // Migrate and expect the version to be bumped
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[1], attr("from_version", "0.1.0"));
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+        // Expect the poll to have numbered options and be open forever
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, Some(PollStatus::Open));
+        let poll = res.poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                option(0, "Cosmos Hub", 0),
+                option(1, "Juno", 1),
+                option(2, "Osmosis", 1)
+            ]
+        );
+        assert_eq!(poll.poll_type, PollType::SingleChoice);
+
+        // Expect the ballots to be readable and indexed
+        let msg = QueryMsg::BallotsByVoter {
+            voter: ADDR2.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: BallotsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.ballots[0].options, vec![2]);
+        assert_eq!(res.ballots[0].weight, Uint128::new(1));
+
+        // Expect a migrated ballot to be revoked correctly when the voter changes their vote
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: "Osmosis".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.winner, Some(2));
+
+        // Expect the admin to carry over
+        let msg = ExecuteMsg::UpdateAdmin {
+            admin: ADDR2.to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg).unwrap();
+
+        // Migrating again is a no-op
+        let _res = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // A different contract cannot be migrated
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+        let err = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, ContractError::CannotMigrate { .. }));
+
+        // Nor can a newer version
+        set_contract_version(deps.as_mut().storage, "crates.io:cw-starter", "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, ContractError::CannotMigrateVersion { .. }));
+    }
 }
@@ -1,5 +1,6 @@
 // import dependencies
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 // derive traits
@@ -10,11 +11,144 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    // derive overflow error for a tally that would drop below zero or overflow
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     // derive unauthorized error
     #[error("Unauthorized")]
     Unauthorized {},
 
+    // derive cannot migrate error for a migration from a different contract
+    #[error("Cannot migrate from {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    // derive cannot migrate version error for a migration to an older version
+    #[error("Cannot migrate from newer version {previous_version}")]
+    CannotMigrateVersion { previous_version: String },
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
+    // derive poll not found error for a message naming a poll that does not exist
+    #[error("Poll {poll_id} not found")]
+    PollNotFound { poll_id: String },
+
+    // derive poll already exists error for creating a poll with an id that is already taken
+    #[error("Poll {poll_id} already exists")]
+    PollAlreadyExists { poll_id: String },
+
+    // derive not creator or admin error for a poll management message sent by someone else
+    #[error("Only the poll creator or the contract admin can manage this poll")]
+    NotCreatorOrAdmin {},
+
+    // derive poll has votes error for deleting or editing a poll that has already been voted in
+    #[error("Poll already has votes")]
+    PollHasVotes {},
+
+    // derive invalid fee error for a poll creation that does not pay exactly the configured fee
+    #[error("Creating a poll costs {amount} {denom}")]
+    InvalidFee { denom: String, amount: Uint128 },
+
+    // derive no fees error for a withdrawal when the treasury is empty
+    #[error("No fees to withdraw")]
+    NoFeesToWithdraw {},
+
     // derive too many options error for a poll with too many options
     #[error("Too many poll options")]
     TooManyOptions {},
+
+    // derive empty option error for a poll option that is blank
+    #[error("Poll options cannot be empty")]
+    EmptyOption {},
+
+    // derive duplicate option error for a poll listing the same option twice
+    #[error("Poll option {option} is listed more than once")]
+    DuplicateOption { option: String },
+
+    // derive empty question error for a poll with a blank question
+    #[error("Poll question cannot be empty")]
+    EmptyQuestion {},
+
+    // derive question too long error for a poll question over the length limit
+    #[error("Poll question is longer than {max} characters")]
+    QuestionTooLong { max: usize },
+
+    // derive invalid option error for a vote naming an option the poll does not have
+    #[error("Poll has no option {option}")]
+    InvalidOption { option: String },
+
+    // derive invalid voting window error for a poll whose end is already passed or not after its start
+    #[error("Invalid voting window")]
+    InvalidVotingWindow {},
+
+    // derive poll not started error for a vote cast before the poll opens
+    #[error("Poll has not started yet")]
+    PollNotStarted {},
+
+    // derive poll closed error for a vote cast after the poll has ended
+    #[error("Poll is closed")]
+    PollClosed {},
+
+    // derive cw20 no snapshot error for a cw20 weight source that cannot report balances at a past height
+    #[error("Token {addr} does not support balance snapshots")]
+    Cw20NoSnapshot { addr: String },
+
+    // derive no voting power error for a voter whose weight in the poll's weight source is zero
+    #[error("Sender has no voting power in this poll")]
+    NoVotingPower {},
+
+    // derive invalid max choices error for an approval poll allowing zero or more choices than it has options
+    #[error("Invalid max choices for approval poll")]
+    InvalidMaxChoices {},
+
+    // derive no choices error for a ballot that picks nothing
+    #[error("Ballot must choose at least one option")]
+    NoChoices {},
+
+    // derive too many choices error for a ballot picking more options than the poll type allows
+    #[error("Too many choices, at most {max} allowed")]
+    TooManyChoices { max: u32 },
+
+    // derive commit reveal poll error for a plain vote in a poll that only takes committed votes
+    #[error("Poll only accepts committed votes")]
+    CommitRevealPoll {},
+
+    // derive not commit reveal poll error for a committed or revealed vote in an ordinary poll
+    #[error("Poll does not accept committed votes")]
+    NotCommitRevealPoll {},
+
+    // derive commit reveal single choice error for a commit-reveal poll that is not single-choice
+    #[error("Commit-reveal polls must be single-choice")]
+    CommitRevealSingleChoice {},
+
+    // derive invalid commitment error for a commitment that is not a sha256 hash
+    #[error("Commitment must be a 32 byte sha256 hash")]
+    InvalidCommitment {},
+
+    // derive no commitment error for a reveal without a matching commit
+    #[error("No committed vote to reveal")]
+    NoCommitment {},
+
+    // derive commitment mismatch error for a reveal whose vote and salt do not hash to the commitment
+    #[error("Vote and salt do not match the commitment")]
+    CommitmentMismatch {},
+
+    // derive reveal not started error for a reveal during the commit phase
+    #[error("Poll is still taking commitments")]
+    RevealNotStarted {},
+
+    // derive duplicate choice error for a ballot that picks the same option twice
+    #[error("Option {option} chosen more than once")]
+    DuplicateChoice { option: u32 },
+}
+
+// convert semver parsing errors so migrate can use ? on version strings
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
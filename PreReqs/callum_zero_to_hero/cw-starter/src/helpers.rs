@@ -6,7 +6,10 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // import dependencies from cosmwasm_std library
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg};
+use cosmwasm_std::{to_binary, Addr, Binary, CosmosMsg, StdResult, WasmMsg};
+
+// import Sha256 from sha2 library to hash commit-reveal votes
+use sha2::{Digest, Sha256};
 
 // import crate dependencies from msg.rs file
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
@@ -35,6 +38,21 @@ impl CwTemplateContract {
         .into())
     }
 }
+
+// define the vote_commitment function which builds the commitment a voter sends with CommitVote
+// it is the sha256 of the voter's address and the poll id, each prefixed with its big-endian u32 byte length, then the big-endian option id and the salt,
+// so a commitment cannot be copied by another voter or replayed in another poll
+pub fn vote_commitment(voter: &Addr, poll_id: &str, option_id: u32, salt: &str) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update((voter.as_bytes().len() as u32).to_be_bytes());
+    hasher.update(voter.as_bytes());
+    hasher.update((poll_id.len() as u32).to_be_bytes());
+    hasher.update(poll_id.as_bytes());
+    hasher.update(option_id.to_be_bytes());
+    hasher.update(salt.as_bytes());
+    Binary::from(hasher.finalize().to_vec())
+}
+
 /* commenting out for now so I can compile! No custom responses are being used and I don't really understand this part yet.
     /// Get Custom
     pub fn custom_query<Q, T, CQ>(&self, querier: &Q, val: String) -> StdResult<CustomResponse>
@@ -0,0 +1,235 @@
+#[cfg(test)]
+mod tests {
+    use crate::msg::{
+        BallotsResponse, ExecuteMsg, MigrateMsg, PollResponse, QueryMsg, ResultsResponse,
+    };
+    use crate::state::{
+        LegacyBallot, LegacyConfig, LegacyPoll, PollOption, LEGACY_BALLOTS, LEGACY_CONFIG,
+        LEGACY_POLLS,
+    };
+    use cosmwasm_std::{
+        Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+        Uint128,
+    };
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use serde::{Deserialize, Serialize};
+
+    const ADMIN: &str = "admin";
+    const USER1: &str = "user1";
+    const USER2: &str = "user2";
+    const USER3: &str = "user3";
+
+    // the execute messages of v0.1.0, before polls had windows, weights or option ids
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    enum LegacyExecuteMsg {
+        CreatePoll {
+            poll_id: String,
+            question: String,
+            options: Vec<String>,
+        },
+        Vote {
+            poll_id: String,
+            vote: String,
+        },
+    }
+
+    // a minimal v0.1.0 contract that writes polls and ballots in the original storage layout
+    fn legacy_instantiate(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        cw2::set_contract_version(deps.storage, "crates.io:cw-starter", "0.1.0")?;
+        LEGACY_CONFIG.save(deps.storage, &LegacyConfig { admin: info.sender })?;
+        Ok(Response::new())
+    }
+
+    fn legacy_execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: LegacyExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            LegacyExecuteMsg::CreatePoll {
+                poll_id,
+                question,
+                options,
+            } => {
+                let poll = LegacyPoll {
+                    creator: info.sender,
+                    question,
+                    options: options.into_iter().map(|option| (option, 0)).collect(),
+                };
+                LEGACY_POLLS.save(deps.storage, &poll_id, &poll)?;
+            }
+            LegacyExecuteMsg::Vote { poll_id, vote } => {
+                let mut poll = LEGACY_POLLS.load(deps.storage, &poll_id)?;
+                let key = (info.sender, poll_id.as_str());
+                if let Some(old) = LEGACY_BALLOTS.may_load(deps.storage, key.clone())? {
+                    if let Some(tally) = poll.options.iter_mut().find(|o| o.0 == old.option) {
+                        tally.1 -= 1;
+                    }
+                }
+                let tally = poll
+                    .options
+                    .iter_mut()
+                    .find(|o| o.0 == vote)
+                    .ok_or_else(|| StdError::generic_err("unknown option"))?;
+                tally.1 += 1;
+                LEGACY_BALLOTS.save(deps.storage, key, &LegacyBallot { option: vote })?;
+                LEGACY_POLLS.save(deps.storage, &poll_id, &poll)?;
+            }
+        }
+        Ok(Response::new())
+    }
+
+    fn legacy_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Err(StdError::generic_err("not supported"))
+    }
+
+    pub fn contract_legacy() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(legacy_execute, legacy_instantiate, legacy_query);
+        Box::new(contract)
+    }
+
+    pub fn contract_starter() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            crate::contract::execute,
+            crate::contract::instantiate,
+            crate::contract::query,
+        )
+        .with_migrate(crate::contract::migrate);
+        Box::new(contract)
+    }
+
+    fn option(id: u32, text: &str, votes: u128) -> PollOption {
+        PollOption {
+            id,
+            text: text.to_string(),
+            votes: Uint128::new(votes),
+        }
+    }
+
+    #[test]
+    fn migrate_populated_v0_1_contract() {
+        let mut app = App::default();
+        let legacy_id = app.store_code(contract_legacy());
+        let starter_id = app.store_code(contract_starter());
+
+        // deploy the v0.1.0 contract and fill it with two polls and some ballots
+        let addr = app
+            .instantiate_contract(
+                legacy_id,
+                Addr::unchecked(ADMIN),
+                &Empty {},
+                &[],
+                "polls",
+                Some(ADMIN.to_string()),
+            )
+            .unwrap();
+        for poll_id in ["poll_a", "poll_b"] {
+            let msg = LegacyExecuteMsg::CreatePoll {
+                poll_id: poll_id.to_string(),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec![
+                    "Cosmos Hub".to_string(),
+                    "Juno".to_string(),
+                    "Osmosis".to_string(),
+                ],
+            };
+            app.execute_contract(Addr::unchecked(USER1), addr.clone(), &msg, &[])
+                .unwrap();
+        }
+        let votes = [
+            (USER1, "poll_a", "Juno"),
+            (USER2, "poll_a", "Juno"),
+            (USER3, "poll_a", "Osmosis"),
+            (USER1, "poll_b", "Cosmos Hub"),
+        ];
+        for (voter, poll_id, vote) in votes {
+            let msg = LegacyExecuteMsg::Vote {
+                poll_id: poll_id.to_string(),
+                vote: vote.to_string(),
+            };
+            app.execute_contract(Addr::unchecked(voter), addr.clone(), &msg, &[])
+                .unwrap();
+        }
+
+        // upgrade to the current code
+        app.migrate_contract(
+            Addr::unchecked(ADMIN),
+            addr.clone(),
+            &MigrateMsg {},
+            starter_id,
+        )
+        .unwrap();
+
+        // the polls keep their tallies with options numbered from 0
+        let res: PollResponse = app
+            .wrap()
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::Poll {
+                    poll_id: "poll_a".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.poll.unwrap().options,
+            vec![
+                option(0, "Cosmos Hub", 0),
+                option(1, "Juno", 2),
+                option(2, "Osmosis", 1)
+            ]
+        );
+
+        // the ballots are indexed by voter and by poll
+        let res: BallotsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::BallotsByVoter {
+                    voter: USER1.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        let ballots: Vec<_> = res
+            .ballots
+            .iter()
+            .map(|b| (b.poll_id.as_str(), b.options.clone()))
+            .collect();
+        assert_eq!(ballots, vec![("poll_a", vec![1]), ("poll_b", vec![0])]);
+
+        // voting keeps working on the migrated polls and replaces the migrated ballots
+        let msg = ExecuteMsg::Vote {
+            poll_id: "poll_a".to_string(),
+            vote: "Osmosis".into(),
+        };
+        app.execute_contract(Addr::unchecked(USER2), addr.clone(), &msg, &[])
+            .unwrap();
+        let res: ResultsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::Results {
+                    poll_id: "poll_a".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.winner, Some(2));
+
+        // the v0.1.0 admin is still the admin
+        let msg = ExecuteMsg::UpdateAdmin {
+            admin: USER1.to_string(),
+        };
+        app.execute_contract(Addr::unchecked(USER2), addr.clone(), &msg, &[])
+            .unwrap_err();
+        app.execute_contract(Addr::unchecked(ADMIN), addr, &msg, &[])
+            .unwrap();
+    }
+}
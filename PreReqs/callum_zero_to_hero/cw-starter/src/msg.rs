@@ -1,52 +1,208 @@
 // messages used by the contract
 
 // import dependencies
-use crate::state::{Ballot, Poll};
+use crate::state::{Ballot, Poll, PollFee, PollOption, PollStatus, PollType};
+use cosmwasm_std::{Addr, Binary};
+use cw20::Cw20ReceiveMsg;
+use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-// define InstantiateMsg struct with a single field called admin which is an Option<String>, so it can be a String or nothing
+// define InstantiateMsg struct with three fields: admin which is an Option<String>, so it can be a String or nothing,
+// creation_fee which is the optional native or cw20 fee every CreatePoll must pay, and max_options which defaults to 10
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
     pub admin: Option<String>,
+    #[serde(default)]
+    pub creation_fee: Option<PollFee>,
+    #[serde(default)]
+    pub max_options: Option<u32>,
 }
 
-// define ExecuteMsg enumerator with variants for CreatePoll, and Vote
+// define ExecuteMsg enumerator with variants for CreatePoll, Vote, CastBallot, the poll lifecycle messages, and UpdateAdmin
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    CreatePoll {
+    // create a poll, paying the creation fee in native funds if one is configured
+    CreatePoll(CreatePollMsg),
+    // create a poll paying a cw20 creation fee, the embedded msg is a ReceiveMsg
+    Receive(Cw20ReceiveMsg),
+    // vote for a single option, works for every poll type
+    Vote {
         poll_id: String,
-        question: String,
-        options: Vec<String>,
+        vote: OptionRef,
     },
-    Vote {
+    // vote for several options at once, the approved options of an approval poll or the preferences of a ranked-choice poll in order
+    CastBallot {
+        poll_id: String,
+        choices: Vec<OptionRef>,
+    },
+    // commit a hidden vote in a commit-reveal poll, the commitment is the sha256 built by helpers::vote_commitment
+    CommitVote {
+        poll_id: String,
+        commitment: Binary,
+    },
+    // reveal a committed vote once the commit phase is over, it is only counted if it matches the commitment
+    RevealVote {
+        poll_id: String,
+        vote: OptionRef,
+        salt: String,
+    },
+    // stop a poll accepting votes before its end, creator or admin only
+    ClosePoll {
         poll_id: String,
-        vote: String,
     },
+    // remove a poll nobody has voted in, creator or admin only
+    DeletePoll {
+        poll_id: String,
+    },
+    // replace the question and/or options of a poll nobody has voted in, creator or admin only
+    EditPoll {
+        poll_id: String,
+        question: Option<String>,
+        options: Option<Vec<String>>,
+    },
+    // hand the contract admin role to another address, admin only
+    UpdateAdmin {
+        admin: String,
+    },
+    // send every collected creation fee to the recipient, admin only
+    WithdrawFees {
+        recipient: String,
+    },
+}
+
+// define ReceiveMsg enumerator with the messages that can be sent along with cw20 tokens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    CreatePoll(CreatePollMsg),
+}
+
+// define OptionRef enumerator so a vote can name an option either by its numeric id or by its text
+// it is untagged so `"vote": 1` picks option 1 and `"vote": "Juno"` picks the option with that text
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum OptionRef {
+    Id(u32),
+    Text(String),
+}
+
+// implement From so option ids and texts can be turned into an OptionRef with .into()
+impl From<u32> for OptionRef {
+    fn from(id: u32) -> Self {
+        OptionRef::Id(id)
+    }
+}
+
+impl From<&str> for OptionRef {
+    fn from(text: &str) -> Self {
+        OptionRef::Text(text.to_string())
+    }
+}
+
+impl From<String> for OptionRef {
+    fn from(text: String) -> Self {
+        OptionRef::Text(text)
+    }
+}
+
+// define CreatePollMsg struct holding everything needed to create a poll
+// start and end are optional so a poll can open immediately and/or never close
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct CreatePollMsg {
+    pub poll_id: String,
+    pub question: String,
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub start: Option<Expiration>,
+    #[serde(default)]
+    pub end: Option<Expiration>,
+    // where vote weights come from, None means one vote per address
+    #[serde(default)]
+    pub weight_source: Option<WeightSourceMsg>,
+    // what kind of ballot the poll accepts, defaults to single-choice
+    #[serde(default)]
+    pub poll_type: PollType,
+    // makes the poll a commit-reveal poll whose votes can be revealed after end until reveal_end, requires end
+    #[serde(default)]
+    pub reveal_end: Option<Expiration>,
+}
+
+// define WeightSourceMsg enumerator, the unvalidated form of state::WeightSource chosen at poll creation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightSourceMsg {
+    // weight voters by their cw4 group membership at the poll's creation height
+    Cw4Group { addr: String },
+    // weight voters by their cw20 balance at the poll's creation height
+    // the token must answer Cw20SnapshotQueryMsg::BalanceAtHeight, which the cw20 spec does not include,
+    // so a plain cw20-base token is refused at CreatePoll with ContractError::Cw20NoSnapshot
+    Cw20Balance { addr: String },
+}
+
+// define Cw20SnapshotQueryMsg enumerator with the balance snapshot query a cw20 token must answer to weight a poll
+// it is not part of the cw20 spec, so only tokens that snapshot their balances can be used, it returns a cw20 BalanceResponse
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20SnapshotQueryMsg {
+    BalanceAtHeight { address: String, height: u64 },
 }
 
-// define QueryMsg enumerator with variants for AllPolls, Poll, and Vote
-// a user can query all the polls, a specific poll, or a specific user's vote for a a specific poll
+// define QueryMsg enumerator with variants for AllPolls, PollsByCreator, Poll, Vote, BallotsByVoter, Results, CommitCount, and UnrevealedCommitments
+// a user can page through all the polls or the polls of one creator, query a specific poll, a specific user's vote for a a specific poll,
+// every ballot a user has cast, the counted results of a poll, or how a commit-reveal poll's commitments were revealed
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    AllPolls {},
+    AllPolls {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    PollsByCreator {
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     Poll { poll_id: String },
     Vote { poll_id: String, address: String },
+    BallotsByVoter {
+        voter: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Results { poll_id: String },
+    CommitCount { poll_id: String },
+    // only answered once the reveal phase is over, so it cannot be used to chase voters who have not revealed yet
+    UnrevealedCommitments {
+        poll_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
-// define the response to the AllPolls query as a vector of Polls
+// define the response to the AllPolls and PollsByCreator queries as a page of polls ordered by poll id
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct AllPollsResponse {
-    pub polls: Vec<Poll>,
+    pub polls: Vec<PollInfo>,
+}
+
+// define a poll in a listing with its id and its status at the current block
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PollInfo {
+    pub poll_id: String,
+    pub poll: Poll,
+    pub status: PollStatus,
 }
 
 // define the response to the Poll query as an option of a poll's ID or nothing if it does not exist
+// status is worked out against the current block so clients can show upcoming, open and closed polls
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct PollResponse {
     pub poll: Option<Poll>,
+    pub status: Option<PollStatus>,
 }
 
 // define the repsponse to the Vote query as an option of a ballot or nothing if it does not exist
@@ -55,7 +211,48 @@ pub struct VoteResponse {
     pub vote: Option<Ballot>,
 }
 
-// define a blank MigrateMsg struct with no possible enumerations
+// define the response to the BallotsByVoter query as a page of ballots ordered by poll id
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BallotsResponse {
+    pub ballots: Vec<Ballot>,
+}
+
+// define the response to the Results query as the counting rounds and the id of the winning option if there is one
+// single-choice and approval polls are counted in one round, ranked-choice polls have one round per instant-runoff elimination
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ResultsResponse {
+    pub rounds: Vec<RoundResponse>,
+    pub winner: Option<u32>,
+}
+
+// define a single counting round as the tally of each remaining option and the ids of the options eliminated at the end of the round
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoundResponse {
+    pub tallies: Vec<PollOption>,
+    pub eliminated: Vec<u32>,
+}
+
+// define the response to the CommitCount query as the number of votes committed and revealed in a commit-reveal poll
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CommitCountResponse {
+    pub commits: u32,
+    pub reveals: u32,
+}
+
+// define the response to the UnrevealedCommitments query as a page of the commitments never revealed, ordered by voter
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CommitmentsResponse {
+    pub commitments: Vec<CommitmentInfo>,
+}
+
+// define an unrevealed commitment as the voter and the hash they committed
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CommitmentInfo {
+    pub voter: Addr,
+    pub commitment: Binary,
+}
+
+// define a blank MigrateMsg struct with no fields, migrate works out what to convert from the stored contract version
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum MigrateMsg {}
+pub struct MigrateMsg {}
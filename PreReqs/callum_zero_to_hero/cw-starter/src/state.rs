@@ -4,37 +4,207 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-// import Addr from cosmwasm_std library
-use cosmwasm_std::Addr;
+// import Addr, Binary, BlockInfo and Uint128 from cosmwasm_std library
+use cosmwasm_std::{Addr, Binary, BlockInfo, Uint128};
 
-// import Item and Map from cw_storage_plus library so we can store data in the contract's storage/state
-use cw_storage_plus::{Item, Map};
+// import Expiration from cw_utils library so polls can open and close at a block height or time
+use cw_utils::Expiration;
 
-// define the Config struct with a single field called admin which is an Addr
+// import Denom from cw20 library so poll creation fees can be paid in a native coin or a cw20 token
+use cw20::Denom;
+
+// import Item, Map and the IndexedMap types from cw_storage_plus library so we can store and index data in the contract's storage/state
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+
+// define the Config struct with three fields: admin which is an Addr, the optional fee charged to create a poll, and the most options a poll may have
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub admin: Addr,
+    pub creation_fee: Option<PollFee>,
+    pub max_options: u32,
 }
 
-// define the Poll struct with three fields: creator, question, and options
+// define the PollFee struct with the denom and amount charged to create a poll
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollFee {
+    pub denom: Denom,
+    pub amount: Uint128,
+}
+
+// define the Poll struct with eight fields: creator, question, options, start, end, weight_source, poll_type, and commit_reveal
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Poll {
     pub creator: Addr,
     pub question: String,
-    pub options: Vec<(String, u64)>,
+    // each option has a numeric id and the sum of the vote weights cast for it
+    // for ranked-choice polls this is the first-preference tally, see QueryMsg::Results for the runoff
+    pub options: Vec<PollOption>,
+    // the poll accepts votes once start has expired, None means it is open from creation
+    pub start: Option<Expiration>,
+    // the poll stops accepting votes once end has expired, None means it never closes
+    pub end: Option<Expiration>,
+    // where vote weights come from, None means every ballot counts as 1
+    pub weight_source: Option<WeightSource>,
+    // how many options a ballot may pick and how they are counted
+    pub poll_type: PollType,
+    // set for commit-reveal polls, which take hidden votes until end and only count the votes revealed before reveal_end
+    #[serde(default)]
+    pub commit_reveal: Option<CommitReveal>,
 }
 
-// define the Ballot struct with a single field called option which is a String
+// define the CommitReveal struct with the end of the reveal phase and how many votes have been committed and revealed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CommitReveal {
+    pub reveal_end: Expiration,
+    pub commits: u32,
+    pub reveals: u32,
+}
+
+// define the PollOption struct with three fields: the option's id, its text, and the sum of the vote weights cast for it
+// ids are assigned in order from 0 when the options are set, so they stay stable however the text is written
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollOption {
+    pub id: u32,
+    pub text: String,
+    pub votes: Uint128,
+}
+
+// define the PollType enumerator with the kinds of ballot a poll accepts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PollType {
+    // pick exactly one option
+    #[default]
+    SingleChoice,
+    // pick up to max_choices options, each picked option receives the voter's full weight
+    Approval { max_choices: u32 },
+    // rank any number of options in order of preference, tallied by instant-runoff
+    RankedChoice,
+}
+
+// define the WeightSource enumerator with the external contracts a poll can take its vote weights from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightSource {
+    // weight is the voter's cw4 group membership weight at the height the poll was created
+    Cw4Group { addr: Addr, height: u64 },
+    // weight is the voter's cw20 balance at the height the poll was created, so tokens moved after voting cannot vote again
+    Cw20Balance { addr: Addr, height: u64 },
+}
+
+// define the PollStatus enumerator so clients can tell upcoming, open, revealing and closed polls apart
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollStatus {
+    Upcoming,
+    Open,
+    // a commit-reveal poll between its end and its reveal_end, committed votes can be revealed but no new votes committed
+    Revealing,
+    Closed,
+}
+
+// implement helper functions on the Poll struct
+impl Poll {
+    // work out the status of the poll at the given block
+    pub fn status(&self, block: &BlockInfo) -> PollStatus {
+        if self.end.is_some_and(|end| end.is_expired(block)) {
+            match &self.commit_reveal {
+                Some(commit_reveal) if !commit_reveal.reveal_end.is_expired(block) => PollStatus::Revealing,
+                _ => PollStatus::Closed,
+            }
+        } else if self.start.is_some_and(|start| !start.is_expired(block)) {
+            PollStatus::Upcoming
+        } else {
+            PollStatus::Open
+        }
+    }
+
+    // a poll is open when it has started and not yet ended
+    pub fn is_open(&self, block: &BlockInfo) -> bool {
+        self.status(block) == PollStatus::Open
+    }
+}
+
+// define the Ballot struct with three fields: the poll it was cast in, the ids of the chosen options, and the weight it was counted with
+// options holds a single entry for single-choice polls and is in order of preference for ranked-choice polls
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Ballot {
-    pub option: String,
+    pub poll_id: String,
+    pub options: Vec<u32>,
+    pub weight: Uint128,
 }
 
 // create a constant called CONFIG which is an Item of struct data type Config, stored as the "config" key in the contract's storage/state
 pub const CONFIG: Item<Config> = Item::new("config");
 
-// create a constant called POLLS which is a Map of struct data type Poll, stored as the "polls" key in the contract's storage/state
-pub const POLLS: Map<&str, Poll> = Map::new("polls");
+// create a constant called COMMITMENTS which is a Map of the hashed votes not yet revealed, keyed by poll id and voter, stored as the "commitments" key in the contract's storage/state
+pub const COMMITMENTS: Map<(&str, &Addr), Binary> = Map::new("commitments");
+
+// create a constant called TREASURY which is an Item of the creation fees collected and not yet withdrawn, stored as the "treasury" key in the contract's storage/state
+pub const TREASURY: Item<Uint128> = Item::new("treasury");
+
+// define the PollIndexes struct with a secondary index so polls can be listed by their creator
+pub struct PollIndexes<'a> {
+    pub creator: MultiIndex<'a, Addr, Poll, String>,
+}
+
+// implement IndexList so the IndexedMap keeps the indexes up to date on every save and remove
+impl<'a> IndexList<Poll> for PollIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Poll>> + '_> {
+        let v: Vec<&dyn Index<Poll>> = vec![&self.creator];
+        Box::new(v.into_iter())
+    }
+}
+
+// create a function called polls which returns an IndexedMap of struct data type Poll keyed by poll id, stored as the "polls" key in the contract's storage/state
+pub fn polls<'a>() -> IndexedMap<'a, &'a str, Poll, PollIndexes<'a>> {
+    let indexes = PollIndexes {
+        creator: MultiIndex::new(|_pk, p| p.creator.clone(), "polls", "polls__creator"),
+    };
+    IndexedMap::new("polls", indexes)
+}
+
+// define the BallotIndexes struct with a secondary index so every ballot of a poll can be read back for tallying
+pub struct BallotIndexes<'a> {
+    pub poll: MultiIndex<'a, String, Ballot, (Addr, String)>,
+}
+
+// implement IndexList so the IndexedMap keeps the indexes up to date on every save and remove
+impl<'a> IndexList<Ballot> for BallotIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Ballot>> + '_> {
+        let v: Vec<&dyn Index<Ballot>> = vec![&self.poll];
+        Box::new(v.into_iter())
+    }
+}
+
+// create a function called ballots which returns an IndexedMap of struct data type Ballot keyed by voter and poll id, stored as the "ballots" key in the contract's storage/state
+pub fn ballots<'a>() -> IndexedMap<'a, (Addr, &'a str), Ballot, BallotIndexes<'a>> {
+    let indexes = BallotIndexes {
+        poll: MultiIndex::new(|_pk, b| b.poll_id.clone(), "ballots", "ballots__poll"),
+    };
+    IndexedMap::new("ballots", indexes)
+}
+
+// define the LegacyConfig, LegacyPoll and LegacyBallot structs with the layout stored by v0.1.0 of the contract
+// they are only read by migrate, which rewrites them into Config, Poll and Ballot under the same keys
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub admin: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyPoll {
+    pub creator: Addr,
+    pub question: String,
+    pub options: Vec<(String, u64)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyBallot {
+    pub option: String,
+}
 
-// create a constant called BALLOTS which is a Map of struct data type Ballot, stored as the "ballots" key in the contract's storage/state
-pub const BALLOTS: Map<(Addr, &str), Ballot> = Map::new("ballots");
+// create constants for the v0.1.0 config, polls and ballots, stored under the same keys the current layout uses
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+pub const LEGACY_POLLS: Map<&str, LegacyPoll> = Map::new("polls");
+pub const LEGACY_BALLOTS: Map<(Addr, &str), LegacyBallot> = Map::new("ballots");